@@ -1,5 +1,13 @@
+mod card_tracker;
 mod cpu;
 mod hand_sorting;
+mod monte_carlo;
+mod planner;
+mod strategy;
 
+pub use self::card_tracker::*;
 pub use self::cpu::*;
 pub use self::hand_sorting::*;
+pub use self::monte_carlo::*;
+pub use self::planner::*;
+pub use self::strategy::*;
@@ -0,0 +1,338 @@
+use crate::game::{
+    compare_hands_with_ruleset,
+    Hand,
+    HandRules,
+    PlayerView,
+    Ruleset,
+};
+use crate::cards::{Card, Deck, PlayedCard, Rank, Suit};
+
+/// The cards a player hasn't seen - everything in the decks that
+/// isn't in their hand or already played. Jokers are counted as
+/// jokers, whatever they stood in for, and each copy of a card
+/// from another deck is tracked separately
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardTracker {
+    unseen: Vec<Card>,
+}
+
+impl CardTracker {
+    /// Every card in the decks except those in `hand`
+    pub fn new(num_decks: u8, num_jokers: u8, hand: &[Card]) -> CardTracker {
+        let mut unseen = Deck::new(num_decks, num_jokers).to_vec();
+
+        for card in hand {
+            if let Some(i) = unseen.iter().position(|c| c == card) {
+                unseen.remove(i);
+            }
+        }
+
+        CardTracker { unseen }
+    }
+
+    /// What the player knows from their hand and every move made so
+    /// far in the game
+    pub fn from_view(view: &PlayerView) -> CardTracker {
+        let mut tracker = CardTracker::new(
+            view.num_decks,
+            view.num_jokers,
+            &view.hand
+        );
+
+        for turn in &view.history {
+            tracker.record_move(&turn.hand.to_cards());
+        }
+
+        tracker
+    }
+
+    pub fn record_move(&mut self, cards: &[PlayedCard]) {
+        for &card in cards {
+            let position = self.unseen.iter()
+                .position(|&c| is_same_card(c, card));
+
+            if let Some(i) = position {
+                self.unseen.remove(i);
+            }
+        }
+    }
+
+    pub fn get_unseen_cards(&self) -> Vec<Card> {
+        self.unseen.clone()
+    }
+
+    pub fn count_unseen_jokers(&self) -> usize {
+        self.unseen.iter().filter(|c| c.get_rank().is_none()).count()
+    }
+
+    /// No unseen card, joker or not, could beat `card` as a single.
+    /// This doesn't allow for bombs
+    pub fn is_guaranteed_single(
+        &self,
+        card: PlayedCard,
+        suit_order: [Suit; 4],
+        rank_order: [Rank; 13],
+        ruleset: &Ruleset,
+    ) -> bool {
+        let mut challengers = self.get_unseen_naturals();
+        if self.count_unseen_jokers() > 0 {
+            challengers.push(PlayedCard::new(
+                rank_order[rank_order.len() - 1],
+                suit_order[suit_order.len() - 1],
+                true
+            ));
+        }
+
+        !challengers.into_iter().any(|challenger| {
+            compare_hands_with_ruleset(
+                Hand::Single(card),
+                Hand::Single(challenger),
+                ruleset,
+                suit_order,
+                rank_order
+            )
+        })
+    }
+
+    /// The ranks that the unseen cards, jokers included, could still
+    /// make a pair of that beats `pair`
+    pub fn get_ranks_beating_pair(
+        &self,
+        pair: Hand,
+        suit_order: [Suit; 4],
+        rank_order: [Rank; 13],
+        ruleset: &Ruleset,
+    ) -> Vec<Rank> {
+        rank_order.iter()
+            .cloned()
+            .filter(|&rank| {
+                self.get_strongest_pair(rank, suit_order)
+                    .into_iter()
+                    .any(|candidate| compare_hands_with_ruleset(
+                        pair,
+                        candidate,
+                        ruleset,
+                        suit_order,
+                        rank_order
+                    ))
+            })
+            .collect()
+    }
+
+    /// Whether the unseen cards, jokers included, could still make
+    /// a straight flush
+    pub fn can_make_straight_flush(&self, hand_rules: &HandRules) -> bool {
+        if !hand_rules.straight_flushes {
+            return false;
+        }
+
+        let naturals = self.get_unseen_naturals();
+        let jokers = self.count_unseen_jokers();
+        let runs = hand_rules.straight_rule.get_runs();
+
+        [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades].iter()
+            .any(|&suit| runs.iter().any(|run| {
+                let missing = run.iter()
+                    .filter(|&&rank| !naturals.contains(
+                        &PlayedCard::new(rank, suit, false)
+                    ))
+                    .count();
+
+                missing <= jokers
+            }))
+    }
+
+    fn get_strongest_pair(
+        &self,
+        rank: Rank,
+        suit_order: [Suit; 4],
+    ) -> Option<Hand> {
+        let mut suits: Vec<Suit> = self.get_unseen_naturals().into_iter()
+            .filter(|c| c.get_rank() == rank)
+            .map(|c| c.get_suit())
+            .collect();
+        suits.sort_by_key(|&suit| {
+            suit_order.iter().rev().position(|&s| s == suit)
+        });
+
+        let mut cards: Vec<PlayedCard> = suits.into_iter()
+            .take(2)
+            .map(|suit| PlayedCard::new(rank, suit, false))
+            .collect();
+        let jokers: Vec<PlayedCard> = suit_order.iter().rev()
+            .filter(|&&suit| cards.iter().all(|c| c.get_suit() != suit))
+            .take(self.count_unseen_jokers())
+            .map(|&suit| PlayedCard::new(rank, suit, true))
+            .collect();
+
+        cards.extend(jokers);
+        cards.truncate(2);
+
+        Hand::build(cards)
+            .filter(|hand| matches!(hand, Hand::Pair(_, _)))
+    }
+
+    fn get_unseen_naturals(&self) -> Vec<PlayedCard> {
+        self.unseen.iter()
+            .filter_map(|&c| match c {
+                Card::Standard { rank, suit, .. } => {
+                    Some(PlayedCard::new(rank, suit, false))
+                },
+                Card::Joker { .. } => None,
+            })
+            .collect()
+    }
+}
+
+fn is_same_card(card: Card, played: PlayedCard) -> bool {
+    match card {
+        Card::Joker { .. } => played.get_is_joker(),
+        Card::Standard { rank, suit, .. } => !played.get_is_joker()
+            && played.get_rank() == rank
+            && played.get_suit() == suit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{Game, DEFAULT_HAND_RULES, DEFAULT_RULESET};
+
+    fn played(rank: Rank, suit: Suit) -> PlayedCard {
+        PlayedCard::new(rank, suit, false)
+    }
+
+    /// a tracker that has seen every natural card except `unseen`
+    fn get_tracker(unseen: &[PlayedCard], num_jokers: u8) -> CardTracker {
+        let mut tracker = CardTracker::new(1, num_jokers, &[]);
+        let seen: Vec<PlayedCard> = tracker.get_unseen_naturals()
+            .into_iter()
+            .filter(|c| !unseen.contains(c))
+            .collect();
+        tracker.record_move(&seen);
+
+        tracker
+    }
+
+    #[test]
+    fn jokers_and_duplicates_are_tracked_separately() {
+        let hand = vec!(
+            Card::Standard { deck_id: 1, rank: Rank::Three, suit: Suit::Clubs },
+        );
+        let mut tracker = CardTracker::new(2, 2, &hand);
+
+        assert_eq!(tracker.get_unseen_cards().len(), 105);
+
+        tracker.record_move(&[
+            played(Rank::Three, Suit::Clubs),
+            PlayedCard::new(Rank::Three, Suit::Hearts, true),
+        ]);
+
+        assert_eq!(tracker.count_unseen_jokers(), 1);
+        assert!(tracker.get_unseen_cards().iter().all(|&c| {
+            c.get_rank() != Some(Rank::Three)
+                || c.get_suit() != Some(Suit::Clubs)
+        }));
+        assert_eq!(
+            tracker.get_unseen_cards().iter()
+                .filter(|c| c.get_rank() == Some(Rank::Three))
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn the_tracker_follows_the_game_history() {
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 2, &ids, get_suit_array(), DEFAULT_RULESET, 3
+        );
+        for _ in 0..6 {
+            let id = game.get_next_player().unwrap();
+            let cards = game.suggest_move(&id).unwrap();
+            game.play_move(&id, cards).unwrap();
+        }
+
+        let view = game.view_for("a").unwrap();
+        let tracker = CardTracker::from_view(&view);
+        let opponent_cards: usize = view.opponents.iter()
+            .map(|p| p.card_count)
+            .sum();
+
+        assert_eq!(tracker.get_unseen_cards().len(), opponent_cards);
+    }
+
+    #[test]
+    fn a_single_is_guaranteed_once_everything_above_it_is_played() {
+        let king = played(Rank::King, Suit::Spades);
+        let guaranteed = |tracker: CardTracker| tracker.is_guaranteed_single(
+            king,
+            get_suit_array(),
+            get_rank_array(),
+            &DEFAULT_RULESET
+        );
+
+        assert!(!guaranteed(CardTracker::new(1, 0, &[])));
+        assert!(guaranteed(get_tracker(&[
+            played(Rank::King, Suit::Hearts),
+            played(Rank::Three, Suit::Clubs),
+        ], 0)));
+        assert!(!guaranteed(get_tracker(&[], 1)));
+    }
+
+    #[test]
+    fn it_finds_the_ranks_that_can_still_make_a_higher_pair() {
+        let queens = Hand::Pair(
+            played(Rank::Queen, Suit::Clubs),
+            played(Rank::Queen, Suit::Hearts),
+        );
+        let unseen = [
+            played(Rank::King, Suit::Clubs),
+            played(Rank::King, Suit::Hearts),
+            played(Rank::Ace, Suit::Spades),
+            played(Rank::Two, Suit::Diamonds),
+            played(Rank::Jack, Suit::Spades),
+            played(Rank::Jack, Suit::Diamonds),
+        ];
+        let ranks_beating = |tracker: CardTracker| {
+            tracker.get_ranks_beating_pair(
+                queens,
+                get_suit_array(),
+                get_rank_array(),
+                &DEFAULT_RULESET
+            )
+        };
+
+        assert_eq!(ranks_beating(get_tracker(&unseen, 0)), vec!(Rank::King));
+        assert_eq!(
+            ranks_beating(get_tracker(&unseen, 1)),
+            vec!(Rank::King, Rank::Ace, Rank::Two)
+        );
+    }
+
+    #[test]
+    fn straight_flushes_need_every_card_of_a_run_or_jokers_to_fill_it() {
+        let mut unseen = vec!(
+            played(Rank::Three, Suit::Hearts),
+            played(Rank::Four, Suit::Hearts),
+            played(Rank::Five, Suit::Hearts),
+            played(Rank::Six, Suit::Hearts),
+        );
+
+        assert!(!get_tracker(&unseen, 0).can_make_straight_flush(
+            &DEFAULT_HAND_RULES
+        ));
+        assert!(get_tracker(&unseen, 1).can_make_straight_flush(
+            &DEFAULT_HAND_RULES
+        ));
+
+        unseen.push(played(Rank::Seven, Suit::Hearts));
+        assert!(get_tracker(&unseen, 0).can_make_straight_flush(
+            &DEFAULT_HAND_RULES
+        ));
+        assert!(!get_tracker(&unseen, 0).can_make_straight_flush(
+            &HandRules { straight_flushes: false, ..DEFAULT_HAND_RULES }
+        ));
+    }
+}
@@ -1,20 +1,111 @@
 use crate::game::{
+    BombRule,
     Hand,
+    HandOrdering,
     Player,
-    compare_hands,
+    Ruleset,
+    compare_hands_with_ruleset,
+    get_highest_cards,
+    get_candidate_moves,
     sort_unplayed_cards,
-    FlushPrecedence
+    SubmitError,
 };
 use crate::cards::{Card, PlayedCard, Rank, Suit};
-use super::{find_pairs, get_sets_of_same_rank, find_fct};
+use super::{find_pairs, get_sets_of_same_rank, find_fct, CardTracker};
 
+/// The move the CPU would make, following every rule in `ruleset`.
+/// When the only other option is to pass, a bomb is spent on
+/// anything bigger than a single, or on a single when it leaves the
+/// player close to going out
 pub fn get_move(
     last_move: Option<Hand>,
     player_option: Option<Player>,
     suit_order: [Suit; 4],
     rank_order: [Rank; 13],
-) -> Option<Vec<PlayedCard>> {
-    let player = player_option.unwrap();
+    ruleset: &Ruleset,
+) -> Result<Vec<PlayedCard>, SubmitError> {
+    let hand = get_regular_move(
+        last_move,
+        player_option.clone(),
+        suit_order,
+        rank_order,
+        ruleset
+    )?;
+
+    let (bombs, last_move) = match (ruleset.bombs, last_move) {
+        (Some(bombs), Some(last_move)) if hand.is_empty() => {
+            (bombs, last_move)
+        },
+        _ => return Ok(hand),
+    };
+    let player_hand = player_option.ok_or(SubmitError::UnknownPlayer)?
+        .get_hand();
+    let bomb = get_weakest_bomb(
+        &player_hand,
+        last_move,
+        bombs,
+        suit_order,
+        rank_order,
+        ruleset
+    );
+
+    match bomb {
+        Some(bomb) if last_move.to_cards().len() > 1
+            || player_hand.len() - bomb.len() <= 2 => Ok(bomb),
+        _ => Ok(hand),
+    }
+}
+
+/// Like `get_move`, but using the cards that have been played so
+/// far - when leading with two unpaired cards left, a card nobody
+/// can beat goes first so that the lead comes back for the other
+pub fn get_move_with_tracker(
+    last_move: Option<Hand>,
+    player_option: Option<Player>,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+    tracker: &CardTracker,
+) -> Result<Vec<PlayedCard>, SubmitError> {
+    let hand = get_move(
+        last_move,
+        player_option.clone(),
+        suit_order,
+        rank_order,
+        ruleset
+    )?;
+
+    let player_hand = match (player_option, last_move) {
+        (Some(player), Some(Hand::Pass)) => player.get_hand(),
+        _ => return Ok(hand),
+    };
+    if player_hand.len() != 2 || hand.len() != 1 {
+        return Ok(hand);
+    }
+
+    let guaranteed = convert_to_played(
+        &get_natural_cards(&player_hand),
+        suit_order,
+        rank_order
+    ).into_iter()
+        .find(|&card| tracker.is_guaranteed_single(
+            card,
+            suit_order,
+            rank_order,
+            ruleset
+        ));
+
+    Ok(guaranteed.map(|card| vec!(card)).unwrap_or(hand))
+}
+
+fn get_regular_move(
+    last_move: Option<Hand>,
+    player_option: Option<Player>,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Result<Vec<PlayedCard>, SubmitError> {
+    let player = player_option.ok_or(SubmitError::UnknownPlayer)?;
     let unsorted_player_hand = player.get_hand();
     let mut sorted_player_hand = sort_unplayed_cards(
         &unsorted_player_hand,
@@ -25,11 +116,13 @@ pub fn get_move(
     sorted_player_hand.reverse();
     let player_hand = sorted_player_hand;
 
-    if last_move == None {
-        return Some(get_all_low_cards(&player_hand))
-    } 
+    // a hand of only jokers leads the same way as on a cleared table
+    let move_hand = match last_move {
+        None if get_natural_cards(&player_hand).is_empty() => Hand::Pass,
+        None => return Ok(get_all_low_cards(&player_hand)),
+        Some(hand) => hand,
+    };
 
-    let move_hand = last_move.unwrap();
     match move_hand {
         Hand::Pass => {
 
@@ -37,21 +130,25 @@ pub fn get_move(
             let num_jokers = get_jokers(&player_hand).len();
 
             if cards_left == num_jokers {
-                let hand = match cards_left {
-                    1 | 2 | 3 | 5 => player_hand.clone(),
-                    _ => vec!(
-                            *player_hand.clone().first().unwrap(),
-                        )
-                };
-                return Some(convert_to_played(
-                    &hand,
+                let all_jokers = convert_to_played(
+                    &player_hand,
                     suit_order,
                     rank_order
-                ));
+                );
+                let is_valid = Hand::build_with_rules(
+                    all_jokers.clone(),
+                    &ruleset.hand_rules
+                ).is_some();
+
+                return Ok(if is_valid {
+                    all_jokers
+                } else {
+                    vec!(all_jokers[0])
+                });
             }
 
             let pairs = find_pairs(&player_hand);
-            let fct = find_fct(&player_hand);
+            let fct = find_fct(&player_hand, &ruleset.hand_rules);
 
             let first_pair = if !pairs.is_empty() {
                 Some(pairs.first().unwrap().to_vec())
@@ -70,27 +167,22 @@ pub fn get_move(
                 &player_hand
             );
 
-            if first_fct != None {
-                let card_in_fct = first_fct.iter().any(|t| {
-                    t.iter().any(|&p| {
-                        p == lowest_natural_card[0]
-                    })
+            if let Some(fct) = first_fct {
+                let card_in_fct = fct.iter().any(|&p| {
+                    p == lowest_natural_card[0]
                 });
                 if card_in_fct {
-                    return first_fct.clone();
+                    return Ok(fct);
                 }
             }
 
-            if first_pair != None {
-                let card_in_pair = first_pair.iter().any(|p| {
-                    p[0] == lowest_natural_card[0]
-                });
-                if card_in_pair {
-                    return first_pair.clone();
+            if let Some(pair) = first_pair {
+                if pair[0] == lowest_natural_card[0] {
+                    return Ok(pair);
                 }
             }
 
-            Some(lowest_natural_card)
+            Ok(lowest_natural_card)
         },
         Hand::Single(_) => {
 
@@ -104,14 +196,15 @@ pub fn get_move(
 
             let played_single = 
                 get_lowest_natural_card_against_played(
-                    &single_cards.as_slice(),
+                    single_cards.as_slice(),
                     move_hand,
                     suit_order,
-                    rank_order
+                    rank_order,
+                    ruleset
                 );
 
-            if played_single != None {
-                return played_single;
+            if let Some(single) = played_single {
+                return Ok(single);
             }
 
 
@@ -120,11 +213,12 @@ pub fn get_move(
                     &player_hand,
                     move_hand,
                     suit_order,
-                    rank_order
+                    rank_order,
+                    ruleset
                 );
 
-            if played_single != None {
-                return played_single;
+            if let Some(single) = played_single {
+                return Ok(single);
             }
 
             let jokers = get_jokers(&player_hand);
@@ -134,14 +228,15 @@ pub fn get_move(
                     suit_order,
                     rank_order,
                     move_hand,
+                    ruleset,
                 );
 
-                if player_hand != None {
-                    return player_hand;
+                if let Some(joker) = player_hand {
+                    return Ok(joker);
                 }
             }
 
-            get_pass()
+            Ok(get_pass())
         },
         Hand::Pair(_, _) | Hand::Prial(_, _, _) => {
             let hand = get_beating_multiple_card_hand(
@@ -150,48 +245,116 @@ pub fn get_move(
                 move_hand,
                 suit_order,
                 rank_order,
-            );
+                ruleset,
+            ).or_else(|| get_beating_joker_hand(
+                &player_hand,
+                move_hand,
+                suit_order,
+                rank_order,
+                ruleset,
+            ));
 
-            if hand.is_none() {
-                get_pass()
-            } else {
-                hand
-            }
+            Ok(hand.unwrap_or_else(get_pass))
+
+        },
+        Hand::TwoPair(_, _, _, _) => {
+            let hand = get_beating_two_pair(
+                &player_hand,
+                move_hand,
+                suit_order,
+                rank_order,
+                ruleset,
+            );
 
+            Ok(hand.unwrap_or_else(get_pass))
         },
         Hand::FiveCardTrick(_) => {
-            for trick in find_fct(&player_hand) {
-                let built_hand = Hand::build(
-                    trick.to_vec()
+            for trick in find_fct(&player_hand, &ruleset.hand_rules) {
+                let built_hand = Hand::build_with_rules(
+                    trick.to_vec(),
+                    &ruleset.hand_rules
                 ).unwrap();
-                if compare_hands(
+                if compare_hands_with_ruleset(
                     move_hand,
                     built_hand,
-                    FlushPrecedence::Rank,
+                    ruleset,
                     suit_order,
                     rank_order) {
-                    return Some(trick.to_vec());
+                    return Ok(trick.to_vec());
                 }
             }
-            get_pass()
+
+            let hand = get_beating_joker_hand(
+                &player_hand,
+                move_hand,
+                suit_order,
+                rank_order,
+                ruleset,
+            );
+
+            Ok(hand.unwrap_or_else(get_pass))
         },
     }
     
 }
 
+/// Pick cards to hand over in an exchange between deals - either
+/// the highest cards when forced, or low cards that aren't part
+/// of a pair or better
+pub fn choose_cards_to_give(
+    hand: &[Card],
+    count: usize,
+    must_give_highest: bool,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> Vec<Card> {
+    if must_give_highest {
+        return get_highest_cards(hand, count, suit_order, rank_order);
+    }
+
+    let mut lowest_first = sort_unplayed_cards(hand, suit_order, rank_order);
+    lowest_first.reverse();
+
+    let is_single = |card: &Card| {
+        card.get_rank().is_some() && hand.iter()
+            .filter(|c| c.get_rank() == card.get_rank())
+            .count() == 1
+    };
+    let mut chosen: Vec<Card> = lowest_first.iter()
+        .filter(|c| is_single(c))
+        .take(count)
+        .cloned()
+        .collect();
+
+    for card in lowest_first.iter() {
+        if chosen.len() >= count {
+            break;
+        }
+        if !is_single(card) {
+            chosen.push(*card);
+        }
+    }
+
+    chosen
+}
+
 fn get_beating_multiple_card_hand(
     n: usize,
     player_hand: &[Card],
     move_hand: Hand,
     suit_order: [Suit; 4],
     rank_order: [Rank; 13], 
+    ruleset: &Ruleset,
 ) -> Option<Vec<PlayedCard>> {
     for hand in get_sets_of_same_rank(n, player_hand) {
-        let built_hand = Hand::build(hand.clone()).unwrap();
-        if compare_hands(
+        let built_hand = Hand::build_with_rules(
+            hand.clone(),
+            &ruleset.hand_rules
+        ).unwrap();
+        if compare_hands_with_ruleset(
             move_hand,
             built_hand,
-            FlushPrecedence::Rank,
+            ruleset,
             suit_order,
             rank_order) {
             return Some(hand.clone());
@@ -201,17 +364,96 @@ fn get_beating_multiple_card_hand(
     None
 }
 
-fn get_pass() -> Option<Vec<PlayedCard>>{
-    Some(vec!())
+fn get_beating_joker_hand(
+    player_hand: &[Card],
+    move_hand: Hand,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Vec<PlayedCard>> {
+    if get_jokers(player_hand).is_empty() {
+        return None;
+    }
+
+    get_weakest_candidate(
+        player_hand,
+        |cards, hand| {
+            cards.iter().any(|c| c.get_is_joker())
+                && compare_hands_with_ruleset(
+                    move_hand, hand, ruleset, suit_order, rank_order
+                )
+        },
+        suit_order,
+        rank_order,
+        ruleset,
+    )
+}
+
+fn get_beating_two_pair(
+    player_hand: &[Card],
+    move_hand: Hand,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Vec<PlayedCard>> {
+    get_weakest_candidate(
+        player_hand,
+        |_, hand| compare_hands_with_ruleset(
+            move_hand, hand, ruleset, suit_order, rank_order
+        ),
+        suit_order,
+        rank_order,
+        ruleset,
+    )
+}
+
+fn get_weakest_bomb(
+    player_hand: &[Card],
+    move_hand: Hand,
+    bombs: BombRule,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Vec<PlayedCard>> {
+    get_weakest_candidate(
+        player_hand,
+        |_, hand| bombs.can_bomb(move_hand, hand),
+        suit_order,
+        rank_order,
+        ruleset,
+    )
+}
+
+/// the weakest move the ruleset allows that passes `filter`
+fn get_weakest_candidate<F>(
+    player_hand: &[Card],
+    filter: F,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Vec<PlayedCard>>
+where
+    F: Fn(&[PlayedCard], Hand) -> bool,
+{
+    let ordering = HandOrdering::from_ruleset(ruleset, suit_order, rank_order);
+
+    get_candidate_moves(player_hand, &ruleset.hand_rules).into_iter()
+        .filter_map(|cards| {
+            Hand::build_with_rules(cards.clone(), &ruleset.hand_rules)
+                .filter(|&hand| filter(&cards, hand))
+        })
+        .min_by_key(|&hand| ordering.get_strength_key(hand))
+        .map(|hand| hand.to_cards())
+}
+
+fn get_pass() -> Vec<PlayedCard> {
+    vec!()
 }
 
 fn get_all_low_cards(hand: &[Card]) -> Vec<PlayedCard> {
     let natural_cards = get_natural_cards(hand);
-    let player_card = natural_cards.first();
-    let (_head, tail_cards) = natural_cards.split_at(1);
 
-    if player_card.is_some() {
-        let card = player_card.unwrap();
+    if let Some((card, tail_cards)) = natural_cards.split_first() {
         let mut all_low_cards = vec![
             PlayedCard::new(
                 card.get_rank()
@@ -221,7 +463,8 @@ fn get_all_low_cards(hand: &[Card]) -> Vec<PlayedCard> {
                 false)
         ];
 
-        for c in tail_cards {
+        // four of a rank can only be played as part of a trick
+        for c in tail_cards.iter().take(2) {
             if c.get_rank() == card.get_rank() {
                 all_low_cards.push(
                     PlayedCard::new(
@@ -259,7 +502,8 @@ fn get_lowest_natural_card_against_played(
     hand: &[Card],
     last_move: Hand,
     suit_order: [Suit; 4],
-    rank_order: [Rank; 13]
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
 ) -> Option<Vec<PlayedCard>> {
     let natural_cards = get_natural_cards(hand);
     for player_card in natural_cards {
@@ -272,10 +516,10 @@ fn get_lowest_natural_card_against_played(
                 false
             ))
         ).unwrap();
-        if compare_hands(
+        if compare_hands_with_ruleset(
             last_move,
             player_hand, 
-            FlushPrecedence::Rank,
+            ruleset,
             suit_order,
             rank_order
         ) {
@@ -289,6 +533,7 @@ fn get_winning_joker(
     suit_order: [Suit; 4],
     rank_order: [Rank; 13],
     last_move: Hand,
+    ruleset: &Ruleset,
 ) -> Option<Vec<PlayedCard>> {
     let joker_single = Hand::build(
         vec!(PlayedCard::new(
@@ -298,10 +543,10 @@ fn get_winning_joker(
         ))
     ).unwrap();
 
-    if compare_hands(
+    if compare_hands_with_ruleset(
         last_move,
         joker_single, 
-        FlushPrecedence::Rank,
+        ruleset,
         suit_order,
         rank_order
     ) {
@@ -314,14 +559,14 @@ fn get_winning_joker(
 
 fn get_natural_cards(hand: &[Card]) -> Vec<Card> {
     hand.iter().filter(|c| {
-        c.get_rank() != None
+        c.get_rank().is_some()
     })
     .cloned().collect::<Vec<Card>>()
 }
 
 fn get_jokers(hand: &[Card]) -> Vec<Card>{
     hand.iter().filter(|c| {
-        c.get_rank() == None
+        c.get_rank().is_none()
     })
     .cloned().collect::<Vec<Card>>()
 }
@@ -351,7 +596,14 @@ fn convert_to_played(
 mod tests {
     use super::*;
     use crate::cards::*;
-    use crate::game::{TrickType, Trick};
+    use crate::game::{
+        BombRule,
+        HandRules,
+        TrickType,
+        Trick,
+        DEFAULT_HAND_RULES,
+        DEFAULT_RULESET,
+    };
 
     static DEFAULT_SUIT_ORDER: [Suit; 4] =
         [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades];
@@ -385,8 +637,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Three, Suit::Clubs, false
                 )
@@ -394,6 +647,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ai_does_not_open_with_four_of_a_kind() {
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Diamonds},
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Clubs},
+        );
+        let player = Player::new("cpu".to_string(), hand);
+
+        assert_eq!(
+            get_move(
+                None,
+                Some(player),
+                DEFAULT_SUIT_ORDER,
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
+            ),
+            Ok(vec!(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false),
+                PlayedCard::new(Rank::Three, Suit::Hearts, false),
+                PlayedCard::new(Rank::Three, Suit::Diamonds, false),
+            ))
+        );
+    }
+
     #[test]
     fn ai_plays_the_lowest_single_it_can() {
         let previous_move = Some(Hand::Single(
@@ -411,8 +691,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Four, Suit::Clubs, false
                 )
@@ -435,8 +716,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Three, Suit::Clubs, false
                 )
@@ -460,8 +742,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Three, Suit::Clubs, false
                 )
@@ -486,8 +769,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Two, Suit::Spades, true
                 )
@@ -512,8 +796,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!())
+            Ok(vec!())
         );
     }
 
@@ -536,8 +821,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Three, Suit::Clubs, false
                 ),
@@ -566,8 +852,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!())
+            Ok(vec!())
         );
     }
 
@@ -589,8 +876,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Six, Suit::Clubs, false
                 ),
@@ -621,8 +909,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Six, Suit::Clubs, false
                 ),
@@ -656,8 +945,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!())
+            Ok(vec!())
         );
     }
 
@@ -685,8 +975,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Six, Suit::Clubs, false
                 ),
@@ -740,8 +1031,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Six, Suit::Spades, false
                 ),
@@ -790,8 +1082,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Six, Suit::Spades, false
                 ),
@@ -840,8 +1133,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!())
+            Ok(vec!())
         );
 
     }
@@ -860,8 +1154,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, true)
             ))
         );
@@ -882,8 +1177,32 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
+            ),
+            Ok(vec!(
+                PlayedCard::new(Rank::Three, Suit::Clubs, true),
+                PlayedCard::new(Rank::Three, Suit::Clubs, true)
+            ))
+        );
+    }
+
+    #[test]
+    fn if_ai_leads_with_only_jokers_it_will_play_them() {
+        let hand = vec!(
+            Card::Joker{deck_id: 0},
+            Card::Joker{deck_id: 1},
+        );
+        let player = Player::new("cpu".to_string(), hand);
+
+        assert_eq!(
+            get_move(
+                None,
+                Some(player),
+                DEFAULT_SUIT_ORDER,
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, true),
                 PlayedCard::new(Rank::Three, Suit::Clubs, true)
             ))
@@ -908,8 +1227,9 @@ mod tests {
 
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET,
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, true),
             ))
         );
@@ -931,8 +1251,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, false),
                 PlayedCard::new(Rank::Three, Suit::Clubs, false),
             ))
@@ -955,8 +1276,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, false),
             ))
         );
@@ -979,8 +1301,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Jack, Suit::Clubs, false),
                 PlayedCard::new(Rank::Jack, Suit::Hearts, false),
             ))
@@ -1007,8 +1330,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Queen, Suit::Clubs, false),
             ))
         );
@@ -1032,8 +1356,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, false),
                 PlayedCard::new(Rank::Three, Suit::Spades, false),
             ))
@@ -1069,8 +1394,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(
                     Rank::Four, Suit::Spades, false
                 ),
@@ -1120,8 +1446,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!())
+            Ok(vec!())
         );
     }
 
@@ -1145,8 +1472,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Queen, Suit::Spades, false),
             ))
         );
@@ -1171,8 +1499,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Three, Suit::Clubs, false),
                 PlayedCard::new(Rank::Four, Suit::Hearts, false),
                 PlayedCard::new(Rank::Five, Suit::Clubs, false),
@@ -1203,8 +1532,9 @@ mod tests {
                 Some(player),
                 DEFAULT_SUIT_ORDER,
                 DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Two, Suit::Spades, true),
             ))
         );
@@ -1250,11 +1580,217 @@ mod tests {
                 Some(player),
                 alternative_suit_order,
                 alternative_rank_order,
+                &DEFAULT_RULESET
             ),
-            Some(vec!(
+            Ok(vec!(
                 PlayedCard::new(Rank::Five, Suit::Clubs, false),
             ))
         );
 
     }
+
+    #[test]
+    fn it_uses_a_joker_to_make_a_pair() {
+        let previous_move = Some(Hand::Pair(
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Hearts, false),
+        ));
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::Seven, suit: Suit::Clubs},
+            Card::Joker{deck_id: 0},
+        );
+        let player = Player::new("cpu".to_string(), hand);
+
+        assert_eq!(
+            get_move(
+                previous_move,
+                Some(player),
+                DEFAULT_SUIT_ORDER,
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
+            ),
+            Ok(vec!(
+                PlayedCard::new(Rank::Seven, Suit::Clubs, false),
+                PlayedCard::new(Rank::Seven, Suit::Clubs, true),
+            ))
+        );
+    }
+
+    #[test]
+    fn it_uses_a_joker_to_make_a_five_card_trick() {
+        let previous_move = Hand::build(vec!(
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Four, Suit::Hearts, false),
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Six, Suit::Clubs, false),
+            PlayedCard::new(Rank::Seven, Suit::Clubs, false),
+        ));
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Ten, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Jack, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Queen, suit: Suit::Clubs},
+            Card::Joker{deck_id: 0},
+        );
+        let player = Player::new("cpu".to_string(), hand);
+
+        let cards = get_move(
+            previous_move,
+            Some(player),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            &DEFAULT_RULESET
+        ).unwrap();
+
+        assert_eq!(cards.len(), 5);
+        assert_eq!(cards.iter().filter(|c| c.get_is_joker()).count(), 1);
+    }
+
+    #[test]
+    fn it_gives_away_low_single_cards_in_an_exchange() {
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Two, suit: Suit::Spades},
+            Card::Joker{deck_id: 0},
+        );
+
+        assert_eq!(
+            choose_cards_to_give(
+                &hand, 2, false, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+            ),
+            vec!(
+                Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Spades},
+                Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Clubs},
+            )
+        );
+        assert_eq!(
+            choose_cards_to_give(
+                &hand, 2, true, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+            ),
+            vec!(
+                Card::Joker{deck_id: 0},
+                Card::Standard{deck_id: 0, rank: Rank::Two, suit: Suit::Spades},
+            )
+        );
+    }
+
+    #[test]
+    fn it_spends_a_bomb_rather_than_pass_on_a_pair() {
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Diamonds},
+            Card::Standard{deck_id: 0, rank: Rank::Nine, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Six, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Jack, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::King, suit: Suit::Hearts},
+        );
+        let bombs = Some(BombRule {
+            four_of_a_kind: true,
+            straight_flush: true,
+            five_of_a_kind: true,
+            beats_up_to: 3,
+        });
+        let pair = Hand::Pair(
+            PlayedCard::new(Rank::Two, Suit::Clubs, false),
+            PlayedCard::new(Rank::Two, Suit::Spades, false),
+        );
+        let single = Hand::Single(
+            PlayedCard::new(Rank::Two, Suit::Spades, false)
+        );
+        let play = |last_move, bombs| get_move(
+            Some(last_move),
+            Some(Player::new("a".to_string(), hand.clone())),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            &Ruleset { bombs, ..DEFAULT_RULESET },
+        ).unwrap();
+
+        assert!(play(pair, None).is_empty());
+
+        let bomb = play(pair, bombs);
+        assert_eq!(bomb.len(), 5);
+        assert_eq!(
+            bomb.iter().filter(|c| c.get_rank() == Rank::Nine).count(),
+            4
+        );
+
+        // too many cards left to spend it on a single
+        assert!(play(single, bombs).is_empty());
+    }
+
+    #[test]
+    fn bombs_follow_the_hand_rules_of_the_ruleset() {
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Six, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Seven, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::King, suit: Suit::Clubs},
+        );
+        let bombs = Some(BombRule {
+            four_of_a_kind: false,
+            straight_flush: true,
+            five_of_a_kind: false,
+            beats_up_to: 3,
+        });
+        let pair = Hand::Pair(
+            PlayedCard::new(Rank::Two, Suit::Clubs, false),
+            PlayedCard::new(Rank::Two, Suit::Spades, false),
+        );
+        let play = |hand_rules| get_move(
+            Some(pair),
+            Some(Player::new("a".to_string(), hand.clone())),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            &Ruleset { bombs, hand_rules, ..DEFAULT_RULESET },
+        ).unwrap();
+
+        assert_eq!(play(DEFAULT_HAND_RULES).len(), 5);
+
+        // without straight flushes the run is only a flush
+        assert!(play(HandRules {
+            straight_flushes: false,
+            ..DEFAULT_HAND_RULES
+        }).is_empty());
+    }
+
+    #[test]
+    fn it_leads_a_card_nobody_can_beat_to_set_up_going_out() {
+        let hand = vec!(
+            Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::King, suit: Suit::Spades},
+        );
+        let mut tracker = CardTracker::new(1, 0, &hand);
+        let play = |tracker: &CardTracker| get_move_with_tracker(
+            Some(Hand::Pass),
+            Some(Player::new("a".to_string(), hand.clone())),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            &DEFAULT_RULESET,
+            tracker,
+        ).unwrap();
+
+        assert_eq!(
+            play(&tracker),
+            vec!(PlayedCard::new(Rank::Four, Suit::Clubs, false))
+        );
+
+        for &rank in &[Rank::Ace, Rank::Two] {
+            for &suit in &DEFAULT_SUIT_ORDER {
+                tracker.record_move(&[PlayedCard::new(rank, suit, false)]);
+            }
+        }
+
+        assert_eq!(
+            play(&tracker),
+            vec!(PlayedCard::new(Rank::King, Suit::Spades, false))
+        );
+    }
 }
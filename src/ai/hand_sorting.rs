@@ -1,4 +1,5 @@
 use crate::cards::{Card, PlayedCard, Rank, Suit};
+use crate::game::{Hand, HandRules, StraightRule};
 use std::collections::HashMap;
 
 pub fn find_pairs(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
@@ -9,9 +10,12 @@ pub fn find_prials(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
     get_sets_of_same_rank(3, hand)
 }
 
-pub fn find_fct(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
+pub fn find_fct(
+    hand: &[Card],
+    hand_rules: &HandRules
+) -> Vec<Vec<PlayedCard>> {
     let natural_cards = get_natural_cards(hand.to_vec());
-    let straights = get_straights(&natural_cards);
+    let straights = get_straights(&natural_cards, hand_rules.straight_rule);
     let flushes = get_flushes(&natural_cards);
     let full_houses = get_full_houses(&natural_cards);
     let four_of_a_kinds = get_four_of_a_kinds(&natural_cards);
@@ -22,37 +26,40 @@ pub fn find_fct(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
     five_card_tricks.extend(full_houses);
     five_card_tricks.extend(four_of_a_kinds);
 
-    five_card_tricks
+    five_card_tricks.into_iter()
+        .filter(|trick| Hand::build_with_rules(trick.clone(), hand_rules).is_some())
+        .collect()
 }
 
-fn get_straights(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
+fn get_straights(
+    hand: &[Card],
+    straight_rule: StraightRule
+) -> Vec<Vec<PlayedCard>> {
+    let runs = straight_rule.get_runs();
     let mut straights = vec![];
     for card in hand {
-        let mut sequence = vec![
-            PlayedCard::new(
-                card.get_rank().unwrap(),
-                card.get_suit().unwrap(),
-                false
-            )
-        ];
-        let mut next_rank = get_next_rank(*card);
-        for c2 in hand {
-            if c2.get_rank() == next_rank {
-                sequence.push(PlayedCard::new(
-                    c2.get_rank().unwrap(),
-                    c2.get_suit().unwrap(),
+        for run in runs.iter().filter(|run| card.get_rank() == Some(run[0])) {
+            let mut sequence = vec![
+                PlayedCard::new(
+                    card.get_rank().unwrap(),
+                    card.get_suit().unwrap(),
                     false
-                ));
-                next_rank = get_next_rank(*c2);
+                )
+            ];
+            for &rank in run[1..].iter() {
+                if let Some(c2) = hand.iter().find(|c| c.get_rank() == Some(rank)) {
+                    sequence.push(PlayedCard::new(
+                        rank,
+                        c2.get_suit().unwrap(),
+                        false
+                    ));
+                }
             }
+
             if sequence.len() == 5 {
-                break;
+                straights.push(sequence);
             }
         }
-
-        if sequence.len() == 5 {
-            straights.push(sequence);
-        }
     }
     straights
 }
@@ -63,14 +70,14 @@ fn get_flushes(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
 
     for (r, count) in &counts {
         if *count >= 5 {
-            let flush_suit = Some(*r);
+            let flush_suit = *r;
             let mut flush = vec!();
             for card in hand {
-                if card.get_suit() == flush_suit {
+                if card.get_suit() == Some(flush_suit) {
                     flush.push(
                         PlayedCard::new(
                             card.get_rank().unwrap(),
-                            flush_suit.unwrap(),
+                            flush_suit,
                             false
                         )
                     );
@@ -90,8 +97,8 @@ fn get_flushes(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
 
 fn get_full_houses(hand: &[Card]) -> Vec<Vec<PlayedCard>> {
     let mut full_houses = vec![];
-    let pairs = find_pairs(&hand.to_vec());
-    let prials = find_prials(&hand.to_vec());
+    let pairs = find_pairs(hand);
+    let prials = find_prials(hand);
    
     for prial in &prials {
         for pair in &pairs {
@@ -191,33 +198,16 @@ fn get_suit_counts(cards: &[Card]) -> HashMap<Suit, usize> {
 
 fn get_natural_cards(hand: Vec<Card>) -> Vec<Card> {
     hand.iter().filter(|c| {
-        c.get_rank() != None
+        c.get_rank().is_some()
     })
     .cloned().collect::<Vec<Card>>()
 }
 
-fn get_next_rank(card: Card) -> Option<Rank> {
-    match card.get_rank().unwrap_or(Rank::Two) {
-        Rank::Three => Some(Rank::Four),
-        Rank::Four => Some(Rank::Five),
-        Rank::Five => Some(Rank::Six),
-        Rank::Six => Some(Rank::Seven),
-        Rank::Seven => Some(Rank::Eight),
-        Rank::Eight => Some(Rank::Nine),
-        Rank::Nine => Some(Rank::Ten),
-        Rank::Ten => Some(Rank::Jack),
-        Rank::Jack => Some(Rank::Queen),
-        Rank::Queen => Some(Rank::King),
-        Rank::King => Some(Rank::Ace),
-        Rank::Ace => Some(Rank::Two),
-        Rank::Two => None
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cards::*;
+    use crate::game::DEFAULT_HAND_RULES;
 /*
     static DEFAULT_SUIT_ORDER: [Suit; 4] =
         [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades];
@@ -292,7 +282,7 @@ mod tests {
             Card::Standard{deck_id: 0, rank: Rank::Seven, suit: Suit::Clubs},
         ];
 
-        assert_eq!(find_fct(&hand).len(), 1);
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 1);
     }
 
     #[test]
@@ -305,7 +295,7 @@ mod tests {
             Card::Standard{deck_id: 0, rank: Rank::Ten, suit: Suit::Clubs},
         ];
 
-        assert_eq!(find_fct(&hand).len(), 0);
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 0);
     }
 
     #[test]
@@ -318,7 +308,7 @@ mod tests {
             Card::Standard{deck_id: 0, rank: Rank::Ten, suit: Suit::Clubs},
         ];
 
-        assert_eq!(find_fct(&hand).len(), 1);
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 1);
     }
 
     #[test]
@@ -331,7 +321,7 @@ mod tests {
             Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Diamonds},
         ];
 
-        assert_eq!(find_fct(&hand).len(), 1);
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 1);
     }
 
     #[test]
@@ -343,6 +333,30 @@ mod tests {
             Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Clubs},
             Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Diamonds},
         ];
-        assert_eq!(find_fct(&hand).len(), 1);
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 1);
+    }
+
+    #[test]
+    fn it_finds_straights_allowed_by_the_straight_rule() {
+        let low_ace = HandRules {
+            straight_rule: StraightRule::LowAce,
+            ..DEFAULT_HAND_RULES
+        };
+        let hand = vec![
+            Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Clubs},
+            Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Spades},
+            Card::Standard{deck_id: 0, rank: Rank::Ace, suit: Suit::Hearts},
+            Card::Standard{deck_id: 0, rank: Rank::Two, suit: Suit::Clubs},
+        ];
+
+        assert_eq!(find_fct(&hand, &DEFAULT_HAND_RULES).len(), 0);
+        assert_eq!(find_fct(&hand, &low_ace), vec![vec![
+            PlayedCard::new(Rank::Ace, Suit::Hearts, false),
+            PlayedCard::new(Rank::Two, Suit::Clubs, false),
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Four, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Spades, false),
+        ]]);
     }
 }
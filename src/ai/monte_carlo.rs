@@ -0,0 +1,468 @@
+use crate::game::{
+    Hand,
+    HandType,
+    Player,
+    PlayerView,
+    Round,
+    Ruleset,
+    SubmitError,
+};
+use crate::cards::{get_seeded_rng, PlayedCard};
+use super::{get_move, CardTracker, Strategy};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaChaRng;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+/// rollouts that run longer than this are scored as a last place
+const MAX_ROLLOUT_TURNS: usize = 1000;
+
+/// the most moves that are rolled out - jokers can give a hand
+/// thousands of legal moves
+const MAX_CANDIDATES: usize = 12;
+
+/// Deals the unseen cards out to the opponents many times over,
+/// plays each deal to the end with the greedy heuristic, and
+/// chooses the move with the best average finishing place
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonteCarlo {
+    iterations: usize,
+    #[cfg(not(target_arch = "wasm32"))]
+    time_limit: Option<Duration>,
+    seed: u64,
+}
+
+impl MonteCarlo {
+    /// `iterations` rollouts in all are shared between the most
+    /// promising legal moves. The same seed and view always give
+    /// the same move
+    pub fn new(iterations: usize, seed: u64) -> MonteCarlo {
+        MonteCarlo {
+            iterations,
+            #[cfg(not(target_arch = "wasm32"))]
+            time_limit: None,
+            seed,
+        }
+    }
+
+    /// Stop rolling out once `time_limit` has passed. There is no
+    /// clock under wasm, so this is only available natively
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_time_limit(self, time_limit: Duration) -> MonteCarlo {
+        MonteCarlo { time_limit: Some(time_limit), ..self }
+    }
+
+    /// The finishing places summed over each candidate's rollouts,
+    /// and the number of rollouts each got
+    fn score_candidates(
+        &self,
+        view: &PlayerView,
+        candidates: &[Vec<PlayedCard>],
+        rng: &mut ChaChaRng,
+    ) -> (Vec<usize>, Vec<usize>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        #[cfg(not(target_arch = "wasm32"))]
+        let out_of_time = || deadline.is_some_and(|d| Instant::now() >= d);
+        #[cfg(target_arch = "wasm32")]
+        let out_of_time = || false;
+
+        let mut places = vec![0; candidates.len()];
+        let mut rollouts = vec![0; candidates.len()];
+
+        for i in 0..self.iterations {
+            if out_of_time() {
+                break;
+            }
+
+            let index = i % candidates.len();
+            let round = sample_round(view, rng);
+            places[index] += rollout(round, view, candidates[index].clone());
+            rollouts[index] += 1;
+        }
+
+        (places, rollouts)
+    }
+}
+
+impl Default for MonteCarlo {
+    fn default() -> MonteCarlo {
+        MonteCarlo::new(200, 0)
+    }
+}
+
+impl Strategy for MonteCarlo {
+    fn choose_move(
+        &self,
+        view: &PlayerView
+    ) -> Result<Vec<PlayedCard>, SubmitError> {
+        let mut rng = get_seeded_rng(self.seed);
+        let moves = sample_round(view, &mut rng)
+            .legal_moves(&view.player_id)?;
+        let candidates = get_candidates(
+            moves,
+            &view.ruleset,
+            MAX_CANDIDATES.min(self.iterations.max(1))
+        );
+
+        if candidates.len() < 2 {
+            return Ok(candidates.into_iter().next().unwrap_or_default());
+        }
+
+        let (places, rollouts) = self.score_candidates(
+            view,
+            &candidates,
+            &mut rng
+        );
+
+        // ties go to the move that gets rid of more cards, and moves
+        // the time limit left without a rollout aren't chosen
+        let best = (0..candidates.len())
+            .filter(|&i| rollouts[i] > 0)
+            .min_by(|&a, &b| {
+                (places[a] * rollouts[b]).cmp(&(places[b] * rollouts[a]))
+                    .then(candidates[b].len().cmp(&candidates[a].len()))
+            })
+            .unwrap_or(0);
+
+        Ok(candidates[best].clone())
+    }
+}
+
+/// At most `limit` of the legal `moves`, which are sorted weakest
+/// first. Moves that only differ in what their jokers stand for are
+/// collapsed into the strongest of them. Then the weakest moves of
+/// each size are taken in turn - passing first - preferring those
+/// that use fewer jokers
+fn get_candidates(
+    moves: Vec<Vec<PlayedCard>>,
+    ruleset: &Ruleset,
+    limit: usize,
+) -> Vec<Vec<PlayedCard>> {
+    let mut groups: Vec<(CandidateKey, Vec<PlayedCard>)> = vec![];
+
+    for cards in moves {
+        let hand_type = match Hand::build_with_rules(
+            cards.clone(),
+            &ruleset.hand_rules
+        ) {
+            Some(hand) => hand.get_type(),
+            None => continue,
+        };
+        let mut naturals: Vec<PlayedCard> = cards.iter()
+            .filter(|c| !c.get_is_joker())
+            .cloned()
+            .collect();
+        naturals.sort();
+        let key = (naturals, cards.len(), hand_type);
+
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some(group) => group.1 = cards,
+            None => groups.push((key, cards)),
+        }
+    }
+
+    let mut by_size: Vec<Vec<Vec<PlayedCard>>> = vec![];
+    for (_, cards) in groups {
+        if by_size.len() <= cards.len() {
+            by_size.resize(cards.len() + 1, vec![]);
+        }
+        by_size[cards.len()].push(cards);
+    }
+    for moves in by_size.iter_mut() {
+        moves.sort_by_key(|cards| {
+            cards.iter().filter(|c| c.get_is_joker()).count()
+        });
+    }
+
+    let deepest = by_size.iter()
+        .map(|moves| moves.len())
+        .max()
+        .unwrap_or(0);
+    (0..deepest)
+        .flat_map(|depth| by_size.iter()
+            .filter_map(move |moves| moves.get(depth).cloned()))
+        .take(limit)
+        .collect()
+}
+
+/// the natural cards in a move, how many cards it has, and its shape
+type CandidateKey = (Vec<PlayedCard>, usize, HandType);
+
+/// A round where the opponents hold a random deal of the cards the
+/// player hasn't seen - each opponent gets as many as they really
+/// hold
+pub fn sample_round(view: &PlayerView, rng: &mut ChaChaRng) -> Round {
+    let mut unseen = CardTracker::from_view(view).get_unseen_cards();
+    unseen.shuffle(rng);
+
+    let mut players: Vec<Player> = view.opponents.iter()
+        .map(|opponent| {
+            let split = unseen.len().saturating_sub(opponent.card_count);
+            Player::new(opponent.id.clone(), unseen.split_off(split))
+        })
+        .collect();
+    let seat = view.seat.min(players.len());
+    players.insert(seat, Player::new(view.player_id.clone(), view.hand.clone()));
+
+    Round::new(
+        players,
+        view.next_player.clone(),
+        view.last_move,
+        view.last_player.clone(),
+        view.suit_order,
+        view.rank_order,
+        view.ruleset
+    )
+}
+
+/// the place the player finishes in after making `first_move`,
+/// with everyone playing the greedy heuristic from then on
+fn rollout(
+    round: Round,
+    view: &PlayerView,
+    first_move: Vec<PlayedCard>
+) -> usize {
+    let id = &view.player_id;
+    let last_place = round.get_players().len();
+    let mut round = match round.submit_move(id, first_move) {
+        Ok(round) => round,
+        Err(_) => return last_place,
+    };
+
+    for _ in 0..MAX_ROLLOUT_TURNS {
+        let players = round.get_players();
+        let player_is_out = players.iter()
+            .any(|p| p.get_id() == id && p.get_hand().is_empty());
+
+        if player_is_out {
+            return players.iter()
+                .filter(|p| p.get_hand().is_empty())
+                .count();
+        }
+
+        round = match round.get_next_player()
+            .and_then(|next| play_rollout_move(&round, &next, &view.ruleset)) {
+            Some(round) => round,
+            None => return last_place,
+        };
+    }
+
+    last_place
+}
+
+fn play_rollout_move(
+    round: &Round,
+    id: &str,
+    ruleset: &Ruleset
+) -> Option<Round> {
+    let cards = get_move(
+        round.get_last_move(),
+        round.get_player(id),
+        round.get_suit_order(),
+        round.get_rank_order(),
+        ruleset
+    ).unwrap_or_default();
+
+    round.submit_move(id, cards).ok()
+        .or_else(|| {
+            let fallback = round.legal_moves(id).ok()?
+                .into_iter()
+                .next()?;
+            round.submit_move(id, fallback).ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{Game, Hand, DEFAULT_RULESET};
+    use crate::ai::StrategyRegistry;
+
+    fn get_ids() -> [String; 3] {
+        ["a".to_string(), "b".to_string(), "c".to_string()]
+    }
+
+    fn get_game_in_progress() -> Game {
+        let mut game = Game::new_with_seed(
+            1, 2, &get_ids(), get_suit_array(), DEFAULT_RULESET, 3
+        );
+        for _ in 0..6 {
+            let id = game.get_next_player().unwrap();
+            let cards = game.suggest_move(&id).unwrap();
+            game.play_move(&id, cards).unwrap();
+        }
+
+        game
+    }
+
+    #[test]
+    fn sampled_hands_only_hold_cards_the_player_hasnt_seen() {
+        let game = get_game_in_progress();
+        let id = game.get_next_player().unwrap();
+        let view = game.view_for(&id).unwrap();
+        let mut rng = get_seeded_rng(0);
+
+        let round = sample_round(&view, &mut rng);
+        let played: Vec<PlayedCard> = view.history.iter()
+            .flat_map(|turn| turn.hand.to_cards())
+            .collect();
+        let sampled: Vec<Card> = round.get_players().iter()
+            .flat_map(|p| p.get_hand())
+            .collect();
+
+        assert_eq!(
+            round.get_players().iter()
+                .map(|p| (p.get_id().to_string(), p.get_card_count()))
+                .collect::<Vec<_>>(),
+            game.get_round().get_players().iter()
+                .map(|p| (p.get_id().to_string(), p.get_card_count()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(round.get_player(&id).unwrap().get_hand(), view.hand);
+        assert_eq!(sampled.len() + played.len(), 54);
+        assert_eq!(round.get_next_player(), Some(id));
+    }
+
+    #[test]
+    fn the_same_seed_always_chooses_the_same_legal_move() {
+        let mut game = get_game_in_progress();
+        let id = game.get_next_player().unwrap();
+        let view = game.view_for(&id).unwrap();
+        let strategy = MonteCarlo::new(30, 7);
+
+        let cards = strategy.choose_move(&view).unwrap();
+
+        assert_eq!(strategy.choose_move(&view), Ok(cards.clone()));
+        assert_eq!(
+            strategy.with_time_limit(Duration::from_secs(60))
+                .choose_move(&view),
+            Ok(cards.clone())
+        );
+        assert!(game.play_move(&id, cards).is_ok());
+    }
+
+    #[test]
+    fn it_goes_out_when_it_can() {
+        let game = get_game_in_progress();
+        let id = game.get_next_player().unwrap();
+        let mut view = game.view_for(&id).unwrap();
+        view.hand = vec!(
+            Card::Standard { deck_id: 0, rank: Rank::Two, suit: Suit::Spades },
+        );
+        view.last_move = Some(Hand::Single(
+            PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        ));
+
+        assert_eq!(
+            MonteCarlo::new(10, 0).choose_move(&view),
+            Ok(vec!(PlayedCard::new(Rank::Two, Suit::Spades, false)))
+        );
+    }
+
+    fn get_view_with_jokers() -> PlayerView {
+        let game = get_game_in_progress();
+        let id = game.get_next_player().unwrap();
+        let mut view = game.view_for(&id).unwrap();
+        let card = |rank, suit| Card::Standard { deck_id: 0, rank, suit };
+        view.hand = vec!(
+            card(Rank::Four, Suit::Clubs),
+            card(Rank::Five, Suit::Hearts),
+            card(Rank::Six, Suit::Hearts),
+            card(Rank::Seven, Suit::Hearts),
+            card(Rank::Eight, Suit::Diamonds),
+            card(Rank::Nine, Suit::Spades),
+            card(Rank::Jack, Suit::Clubs),
+            card(Rank::King, Suit::Hearts),
+            Card::Joker { deck_id: 0 },
+            Card::Joker { deck_id: 1 },
+        );
+        view.last_move = Some(Hand::Pass);
+
+        view
+    }
+
+    #[test]
+    fn joker_moves_are_collapsed_before_rolling_out() {
+        let view = get_view_with_jokers();
+        let mut rng = get_seeded_rng(0);
+        let moves = sample_round(&view, &mut rng)
+            .legal_moves(&view.player_id)
+            .unwrap();
+
+        let candidates = get_candidates(
+            moves.clone(),
+            &view.ruleset,
+            moves.len()
+        );
+        let singles: Vec<&Vec<PlayedCard>> = candidates.iter()
+            .filter(|cards| cards.len() == 1)
+            .collect();
+
+        assert!(moves.len() > 1000);
+        assert!(candidates.len() < moves.len() / 2);
+        assert_eq!(singles.len(), 9);
+        assert!(singles.contains(
+            &&vec!(PlayedCard::new(Rank::Two, Suit::Spades, true))
+        ));
+
+        let capped = get_candidates(moves, &view.ruleset, MAX_CANDIDATES);
+        assert_eq!(capped.len(), MAX_CANDIDATES);
+        assert_eq!(capped[0], vec!());
+        assert!([1, 2, 3, 5].iter().all(|&n| {
+            capped.iter().any(|cards| cards.len() == n)
+        }));
+    }
+
+    #[test]
+    fn a_tight_budget_bounds_the_rollouts() {
+        let view = get_view_with_jokers();
+        let mut rng = get_seeded_rng(0);
+        let moves = sample_round(&view, &mut rng)
+            .legal_moves(&view.player_id)
+            .unwrap();
+        let candidates = get_candidates(moves, &view.ruleset, MAX_CANDIDATES);
+        let count_rollouts = |strategy: MonteCarlo| -> usize {
+            let mut rng = get_seeded_rng(0);
+            strategy.score_candidates(&view, &candidates, &mut rng)
+                .1
+                .iter()
+                .sum()
+        };
+
+        assert_eq!(count_rollouts(MonteCarlo::new(5, 0)), 5);
+        assert_eq!(
+            count_rollouts(
+                MonteCarlo::new(1_000_000, 0)
+                    .with_time_limit(Duration::from_secs(0))
+            ),
+            0
+        );
+
+        let cards = MonteCarlo::new(1_000_000, 0)
+            .with_time_limit(Duration::from_secs(0))
+            .choose_move(&view)
+            .unwrap();
+        assert!(candidates.contains(&cards));
+    }
+
+    #[test]
+    fn games_with_a_monte_carlo_seat_run_to_the_end() {
+        let mut registry = StrategyRegistry::default();
+        registry.register("quick-monte-carlo", Box::new(MonteCarlo::new(10, 1)));
+
+        let mut game = Game::new_with_seed(
+            1, 2, &get_ids(), get_suit_array(), DEFAULT_RULESET, 4
+        );
+        game.seat_strategy("a", "quick-monte-carlo").unwrap();
+        game.seat_strategy("b", "greedy-lowest").unwrap();
+        game.seat_strategy("c", "planner").unwrap();
+
+        while game.get_next_player().is_some() {
+            assert!(game.play_seated_move(&registry).is_ok());
+        }
+
+        assert_eq!(game.get_winners().len(), 2);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::game::{
+    compare_hands_with_ruleset,
+    sort_unplayed_cards,
+    Hand,
+    HandOrdering,
+    PlayerView,
+    Ruleset,
+    SubmitError,
+};
+use crate::cards::{Card, PlayedCard, Rank, Suit};
+use super::{find_fct, Strategy};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// A split of a hand into combinations that can each be played
+/// in a single turn
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    combinations: Vec<Vec<PlayedCard>>,
+    control_cards: usize,
+}
+
+impl Plan {
+    /// the combinations in the order they would be led, lowest first
+    pub fn get_combinations(&self) -> Vec<Vec<PlayedCard>> {
+        self.combinations.clone()
+    }
+
+    /// the number of turns needed to empty the hand
+    pub fn get_turns(&self) -> usize {
+        self.combinations.len()
+    }
+
+    /// jokers and cards of the top rank that are not tied up in a
+    /// five card trick - these should win back the lead
+    pub fn get_control_cards(&self) -> usize {
+        self.control_cards
+    }
+}
+
+/// Split the whole hand into the fewest combinations, keeping as
+/// many control cards free as possible. Jokers are kept back as
+/// singles
+pub fn plan_hand(
+    hand: &[Card],
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Plan {
+    let mut naturals: Vec<Card> = sort_unplayed_cards(
+        hand,
+        suit_order,
+        rank_order
+    ).into_iter()
+        .filter(|c| c.get_rank().is_some())
+        .collect();
+    naturals.reverse();
+
+    let top_rank = rank_order[rank_order.len() - 1];
+    let mut splits = BTreeMap::new();
+    let mut combinations = split_hand(&naturals, top_rank, ruleset, &mut splits);
+
+    let joker = PlayedCard::new(top_rank, suit_order[3], true);
+    for _ in naturals.len()..hand.len() {
+        combinations.push(vec!(joker));
+    }
+
+    let control_cards = combinations.iter()
+        .map(|c| count_control_cards(c, top_rank))
+        .sum();
+
+    Plan { combinations, control_cards }
+}
+
+/// Follows a plan for the whole hand - leading the combination
+/// holding its lowest card, and otherwise playing the weakest
+/// combination from the plan that beats the last move
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Planner;
+
+impl Strategy for Planner {
+    fn choose_move(
+        &self,
+        view: &PlayerView
+    ) -> Result<Vec<PlayedCard>, SubmitError> {
+        let plan = plan_hand(
+            &view.hand,
+            view.suit_order,
+            view.rank_order,
+            &view.ruleset
+        );
+
+        let last_move = match view.last_move {
+            None | Some(Hand::Pass) => {
+                return Ok(plan.combinations.into_iter()
+                    .next()
+                    .unwrap_or_default());
+            },
+            Some(last_move) => last_move,
+        };
+
+        let ordering = HandOrdering::from_ruleset(
+            &view.ruleset,
+            view.suit_order,
+            view.rank_order
+        );
+
+        Ok(plan.combinations.into_iter()
+            .filter_map(|cards| {
+                Hand::build_with_rules(cards, &view.ruleset.hand_rules)
+            })
+            .filter(|&hand| compare_hands_with_ruleset(
+                last_move,
+                hand,
+                &view.ruleset,
+                view.suit_order,
+                view.rank_order
+            ))
+            .min_by_key(|&hand| ordering.get_strength_key(hand))
+            .map(|hand| hand.to_cards())
+            .unwrap_or_default())
+    }
+}
+
+/// the best split of `cards`, which are sorted lowest first. Every
+/// split has to play the lowest card somewhere, so only the
+/// combinations holding it are tried
+fn split_hand(
+    cards: &[Card],
+    top_rank: Rank,
+    ruleset: &Ruleset,
+    splits: &mut BTreeMap<Vec<Card>, Vec<Vec<PlayedCard>>>,
+) -> Vec<Vec<PlayedCard>> {
+    let lowest = match cards.first() {
+        Some(&card) => to_played(card),
+        None => return vec!(),
+    };
+
+    if let Some(split) = splits.get(cards) {
+        return split.clone();
+    }
+
+    let same_rank: Vec<PlayedCard> = cards.iter()
+        .map(|&c| to_played(c))
+        .filter(|c| c.get_rank() == lowest.get_rank())
+        .take(3)
+        .collect();
+
+    let mut options: Vec<Vec<PlayedCard>> = (1..=same_rank.len())
+        .map(|n| same_rank[..n].to_vec())
+        .collect();
+    options.extend(
+        find_fct(cards, &ruleset.hand_rules).into_iter()
+            .filter(|trick| trick.contains(&lowest))
+    );
+
+    let best = options.into_iter()
+        .map(|combination| {
+            let rest = remove_cards(cards, &combination);
+            let mut split = vec!(combination);
+            split.extend(split_hand(&rest, top_rank, ruleset, splits));
+            split
+        })
+        .min_by_key(|split| {
+            let control_cards: usize = split.iter()
+                .map(|c| count_control_cards(c, top_rank))
+                .sum();
+            (split.len(), Reverse(control_cards))
+        })
+        .unwrap_or_default();
+
+    splits.insert(cards.to_vec(), best.clone());
+
+    best
+}
+
+fn count_control_cards(combination: &[PlayedCard], top_rank: Rank) -> usize {
+    if combination.len() > 3 {
+        return 0;
+    }
+
+    combination.iter()
+        .filter(|c| c.get_is_joker() || c.get_rank() == top_rank)
+        .count()
+}
+
+fn remove_cards(cards: &[Card], played: &[PlayedCard]) -> Vec<Card> {
+    let mut remaining = cards.to_vec();
+    for &p in played {
+        if let Some(i) = remaining.iter().position(|&c| to_played(c) == p) {
+            remaining.remove(i);
+        }
+    }
+
+    remaining
+}
+
+fn to_played(card: Card) -> PlayedCard {
+    PlayedCard::new(
+        card.get_rank().unwrap(),
+        card.get_suit().unwrap(),
+        false
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{Game, DEFAULT_RULESET};
+    use crate::ai::StrategyRegistry;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::Standard { deck_id: 0, rank, suit }
+    }
+
+    fn get_straight_and_pairs() -> Vec<Card> {
+        vec!(
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Four, Suit::Diamonds),
+            card(Rank::Five, Suit::Hearts),
+            card(Rank::Six, Suit::Spades),
+            card(Rank::Seven, Suit::Clubs),
+            card(Rank::Seven, Suit::Diamonds),
+            card(Rank::Seven, Suit::Hearts),
+            card(Rank::King, Suit::Spades),
+            card(Rank::King, Suit::Hearts),
+        )
+    }
+
+    fn get_view(hand: Vec<Card>, last_move: Option<Hand>) -> PlayerView {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 1
+        );
+        let mut view = game.view_for("a").unwrap();
+        view.hand = hand;
+        view.last_move = last_move;
+
+        view
+    }
+
+    #[test]
+    fn a_straight_is_not_broken_up_for_a_full_house() {
+        let plan = plan_hand(
+            &get_straight_and_pairs(),
+            get_suit_array(),
+            get_rank_array(),
+            &DEFAULT_RULESET
+        );
+
+        assert_eq!(plan.get_turns(), 3);
+        assert_eq!(plan.get_combinations()[0].len(), 5);
+        assert_eq!(plan.get_control_cards(), 0);
+    }
+
+    #[test]
+    fn splits_that_keep_control_cards_free_are_preferred() {
+        let hand = vec!(
+            card(Rank::Ten, Suit::Clubs),
+            card(Rank::Jack, Suit::Diamonds),
+            card(Rank::Queen, Suit::Hearts),
+            card(Rank::King, Suit::Spades),
+            card(Rank::Ace, Suit::Clubs),
+            card(Rank::Two, Suit::Diamonds),
+            Card::Joker { deck_id: 0 },
+        );
+
+        let plan = plan_hand(
+            &hand,
+            get_suit_array(),
+            get_rank_array(),
+            &DEFAULT_RULESET
+        );
+
+        assert_eq!(plan.get_turns(), 3);
+        assert_eq!(plan.get_control_cards(), 2);
+        assert_eq!(
+            plan.get_combinations()[1],
+            vec!(PlayedCard::new(Rank::Two, Suit::Diamonds, false))
+        );
+        assert_eq!(
+            plan.get_combinations()[2],
+            vec!(PlayedCard::new(Rank::Two, Suit::Spades, true))
+        );
+    }
+
+    #[test]
+    fn the_planner_leads_with_the_combination_holding_its_lowest_card() {
+        let view = get_view(get_straight_and_pairs(), None);
+        let cards = Planner.choose_move(&view).unwrap();
+
+        assert_eq!(cards.len(), 5);
+        assert!(cards.contains(
+            &PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        ));
+    }
+
+    #[test]
+    fn the_planner_follows_with_the_weakest_combination_that_wins() {
+        let last_move = Hand::Pair(
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Spades, false),
+        );
+        let view = get_view(get_straight_and_pairs(), Some(last_move));
+        let cards = Planner.choose_move(&view).unwrap();
+
+        assert_eq!(cards.len(), 2);
+        assert!(cards.iter().all(|c| c.get_rank() == Rank::Seven));
+
+        let last_move = Hand::Single(
+            PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        );
+        let view = get_view(get_straight_and_pairs(), Some(last_move));
+
+        assert_eq!(Planner.choose_move(&view), Ok(vec!()));
+    }
+
+    #[test]
+    fn games_between_planners_run_to_the_end() {
+        let registry = StrategyRegistry::default();
+        let ids = [
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ];
+
+        for seed in 0..5 {
+            let mut game = Game::new_with_seed(
+                1, 2, &ids, get_suit_array(), DEFAULT_RULESET, seed
+            );
+            for id in &ids {
+                game.seat_strategy(id, "planner").unwrap();
+            }
+
+            while game.get_next_player().is_some() {
+                assert!(game.play_seated_move(&registry).is_ok());
+            }
+
+            assert_eq!(game.get_winners().len(), ids.len() - 1);
+        }
+    }
+}
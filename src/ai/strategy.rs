@@ -0,0 +1,184 @@
+use crate::game::{Player, PlayerView, SubmitError};
+use crate::cards::PlayedCard;
+use super::{get_move_with_tracker, CardTracker, MonteCarlo, Planner};
+use std::collections::BTreeMap;
+
+/// A computer player - chooses a move from what its player can see
+pub trait Strategy {
+    fn choose_move(
+        &self,
+        view: &PlayerView
+    ) -> Result<Vec<PlayedCard>, SubmitError>;
+}
+
+/// The built in heuristic - plays the lowest cards that beat the
+/// last move, keeping pairs and tricks together where it can, and
+/// counts the cards that have been played
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GreedyLowest;
+
+impl Strategy for GreedyLowest {
+    fn choose_move(
+        &self,
+        view: &PlayerView
+    ) -> Result<Vec<PlayedCard>, SubmitError> {
+        get_move_with_tracker(
+            view.last_move,
+            Some(Player::new(view.player_id.clone(), view.hand.clone())),
+            view.suit_order,
+            view.rank_order,
+            &view.ruleset,
+            &CardTracker::from_view(view),
+        )
+    }
+}
+
+/// Strategies by name, so that each seat in a game can be given
+/// its own computer player. The default registry holds the built
+/// in strategies
+pub struct StrategyRegistry {
+    strategies: BTreeMap<String, Box<dyn Strategy>>,
+}
+
+impl StrategyRegistry {
+    /// A registry without any strategies
+    pub fn new() -> StrategyRegistry {
+        StrategyRegistry { strategies: BTreeMap::new() }
+    }
+
+    /// Add a strategy, replacing any already registered by that name
+    pub fn register(&mut self, name: &str, strategy: Box<dyn Strategy>) {
+        self.strategies.insert(name.to_string(), strategy);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Strategy> {
+        self.strategies.get(name).map(|s| s.as_ref())
+    }
+
+    pub fn get_names(&self) -> Vec<String> {
+        self.strategies.keys().cloned().collect()
+    }
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> StrategyRegistry {
+        let mut registry = StrategyRegistry::new();
+        registry.register("greedy-lowest", Box::new(GreedyLowest));
+        registry.register("planner", Box::new(Planner));
+        registry.register("monte-carlo", Box::new(MonteCarlo::default()));
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{
+        Game,
+        Ruleset,
+        SeatError,
+        StartingRule,
+        DEFAULT_RULESET,
+    };
+
+    /// plays its highest single, to tell it apart from the default
+    struct HighestSingle;
+
+    impl Strategy for HighestSingle {
+        fn choose_move(
+            &self,
+            view: &PlayerView
+        ) -> Result<Vec<PlayedCard>, SubmitError> {
+            let card = view.hand.iter().rev()
+                .filter_map(|c| match *c {
+                    Card::Standard { rank, suit, .. } => {
+                        Some(PlayedCard::new(rank, suit, false))
+                    },
+                    Card::Joker { .. } => None,
+                })
+                .next();
+
+            Ok(card.into_iter().collect())
+        }
+    }
+
+    #[test]
+    fn the_default_registry_holds_the_built_in_strategies() {
+        let registry = StrategyRegistry::default();
+
+        assert_eq!(
+            registry.get_names(),
+            vec![
+                "greedy-lowest".to_string(),
+                "monte-carlo".to_string(),
+                "planner".to_string(),
+            ]
+        );
+        assert!(registry.get("greedy-lowest").is_some());
+        assert!(StrategyRegistry::new().get("greedy-lowest").is_none());
+    }
+
+    #[test]
+    fn greedy_lowest_matches_the_suggested_move() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 5
+        );
+        let id = game.get_next_player().unwrap();
+        let view = game.view_for(&id).unwrap();
+
+        assert_eq!(
+            GreedyLowest.choose_move(&view),
+            game.suggest_move(&id)
+        );
+    }
+
+    #[test]
+    fn games_play_the_strategy_seated_for_each_player() {
+        let mut registry = StrategyRegistry::default();
+        registry.register("highest-single", Box::new(HighestSingle));
+
+        let ids = ["a".to_string(), "b".to_string()];
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::LowestCardNotRequired,
+            ..DEFAULT_RULESET
+        };
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 5
+        );
+        let first = game.get_next_player().unwrap();
+        let highest = *game.get_player(&first).unwrap()
+            .get_hand()
+            .last()
+            .unwrap();
+
+        assert_eq!(
+            game.play_seated_move(&registry),
+            Err(SeatError::NoStrategySeated)
+        );
+        game.seat_strategy(&first, "cautious").unwrap();
+        assert_eq!(
+            game.play_seated_move(&registry),
+            Err(SeatError::UnknownStrategy("cautious".to_string()))
+        );
+
+        game.seat_strategy(&first, "highest-single").unwrap();
+        let played = game.play_seated_move(&registry).unwrap();
+
+        assert_eq!(played, vec![PlayedCard::new(
+            highest.get_rank().unwrap(),
+            highest.get_suit().unwrap(),
+            false
+        )]);
+        assert_eq!(
+            game.get_seated_strategy(&first),
+            Some("highest-single".to_string())
+        );
+        assert_eq!(
+            game.seat_strategy("nobody", "greedy-lowest"),
+            Err(SeatError::Submit(SubmitError::UnknownPlayer))
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use super::{Rank, Suit};
+use super::{ParseError, Rank, Suit};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -25,6 +27,47 @@ impl Card {
     }
 }
 
+/// `3C`, `10H`, `JK` etc. - the deck id is not shown
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Card::Joker { .. } => write!(f, "JK"),
+            Card::Standard { rank, suit, .. } => {
+                write!(f, "{}", rank)?;
+                fmt::Display::fmt(suit, f)
+            }
+        }
+    }
+}
+
+/// Parsed cards always come from deck 0
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Card, ParseError> {
+        if s == "JK" {
+            return Ok(Card::Joker { deck_id: 0 });
+        }
+
+        let (rank, suit) = split_card_token(s)?;
+        Ok(Card::Standard {
+            deck_id: 0,
+            rank,
+            suit,
+        })
+    }
+}
+
+fn split_card_token(s: &str) -> Result<(Rank, Suit), ParseError> {
+    let suit_index = s.char_indices()
+        .last()
+        .map(|(i, _)| i)
+        .filter(|&i| i > 0)
+        .ok_or_else(|| ParseError::InvalidCard(s.to_string()))?;
+    let (rank, suit) = s.split_at(suit_index);
+
+    Ok((rank.parse()?, suit.parse()?))
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -68,6 +111,34 @@ impl PlayedCard {
     }
 }
 
+/// jokers are prefixed with `*`, e.g. `*AS` for a joker
+/// played as the ace of spades
+impl fmt::Display for PlayedCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_joker {
+            write!(f, "*")?;
+        }
+        write!(f, "{}", self.rank)?;
+        fmt::Display::fmt(&self.suit, f)
+    }
+}
+
+/// accepts `JK=AS` as well as `*AS` for jokers
+impl FromStr for PlayedCard {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<PlayedCard, ParseError> {
+        let (token, is_joker) = match s.strip_prefix('*')
+            .or_else(|| s.strip_prefix("JK=")) {
+            Some(token) => (token, true),
+            None => (s, false),
+        };
+
+        let (rank, suit) = split_card_token(token)?;
+        Ok(PlayedCard::new(rank, suit, is_joker))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -107,4 +178,43 @@ mod tests {
         assert_eq!(played_ace_of_spades.to_card(), ace_of_spades);
         assert_eq!(played_joker.to_card(), joker);
     }
+
+    #[test]
+    fn cards_can_be_displayed_and_parsed() {
+        let ten_of_hearts = Card::Standard {
+            deck_id: 0,
+            rank: Rank::Ten,
+            suit: Suit::Hearts,
+        };
+        let joker = Card::Joker { deck_id: 0 };
+
+        assert_eq!(ten_of_hearts.to_string(), "10H");
+        assert_eq!(format!("{:#}", ten_of_hearts), "10♥");
+        assert_eq!(joker.to_string(), "JK");
+        assert_eq!("10H".parse::<Card>(), Ok(ten_of_hearts));
+        assert_eq!("TH".parse::<Card>(), Ok(ten_of_hearts));
+        assert_eq!("10♥".parse::<Card>(), Ok(ten_of_hearts));
+        assert_eq!("JK".parse::<Card>(), Ok(joker));
+        assert_eq!(
+            "H".parse::<Card>(),
+            Err(ParseError::InvalidCard("H".to_string()))
+        );
+        assert_eq!(
+            "3X".parse::<Card>(),
+            Err(ParseError::InvalidSuit("X".to_string()))
+        );
+    }
+
+    #[test]
+    fn played_cards_can_be_displayed_and_parsed() {
+        let two_of_spades = PlayedCard::new(Rank::Two, Suit::Spades, false);
+        let joker_ace_of_spades = PlayedCard::new(Rank::Ace, Suit::Spades, true);
+
+        assert_eq!(two_of_spades.to_string(), "2S");
+        assert_eq!(joker_ace_of_spades.to_string(), "*AS");
+        assert_eq!(format!("{:#}", joker_ace_of_spades), "*A♠");
+        assert_eq!("2S".parse::<PlayedCard>(), Ok(two_of_spades));
+        assert_eq!("*AS".parse::<PlayedCard>(), Ok(joker_ace_of_spades));
+        assert_eq!("JK=AS".parse::<PlayedCard>(), Ok(joker_ace_of_spades));
+    }
 }
@@ -1,4 +1,6 @@
 use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
 
 use super::{get_rank_array, get_suit_array, Card};
 
@@ -36,7 +38,22 @@ impl Deck {
 
     pub fn shuffle(&mut self) {
         let mut rng = rand::thread_rng();
-        self.0.shuffle(&mut rng);
+        self.shuffle_with_rng(&mut rng);
+    }
+
+    /// Shuffle deterministically - the same seed always
+    /// produces the same order, whatever version of rand is used.
+    /// Each swap is drawn straight from the generator's output
+    pub fn shuffle_with_seed(&mut self, seed: u64) {
+        let mut rng = get_seeded_rng(seed);
+        for i in (1..self.0.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            self.0.swap(i, j);
+        }
+    }
+
+    pub fn shuffle_with_rng<R: Rng>(&mut self, rng: &mut R) {
+        self.0.shuffle(rng);
     }
 
     pub fn deal(&self, players: u8) -> Vec<Vec<Card>> {
@@ -79,6 +96,16 @@ impl Deck {
     }
 }
 
+/// A generator whose output for a seed is fixed - seeded deals are
+/// stored and replayed, so they can't depend on `StdRng`, which may
+/// change between versions of rand
+pub fn get_seeded_rng(seed: u64) -> ChaChaRng {
+    let mut key = [0; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+
+    ChaChaRng::from_seed(key)
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -120,10 +147,32 @@ mod tests {
         let not_deep_equal = original_order
             .iter()
             .zip(new_order)
-            .any(|(a, b)| a.clone() != b.clone());
+            .any(|(a, b)| *a != b);
         assert!(not_deep_equal);
     }
 
+    #[test]
+    fn the_same_seed_gives_the_same_shuffle() {
+        let mut deck1 = Deck::new(1, 2);
+        let mut deck2 = Deck::new(1, 2);
+
+        deck1.shuffle_with_seed(42);
+        deck2.shuffle_with_seed(42);
+
+        assert_eq!(deck1.to_vec(), deck2.to_vec());
+    }
+
+    #[test]
+    fn different_seeds_give_different_shuffles() {
+        let mut deck1 = Deck::new(1, 0);
+        let mut deck2 = Deck::new(1, 0);
+
+        deck1.shuffle_with_seed(1);
+        deck2.shuffle_with_seed(2);
+
+        assert_ne!(deck1.to_vec(), deck2.to_vec());
+    }
+
     #[test]
     fn it_can_deal() {
         let deck = Deck::new(1, 0);
@@ -1,6 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParseError {
+    InvalidRank(String),
+    InvalidSuit(String),
+    InvalidCard(String),
+    InvalidHand(String),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Colour {
@@ -25,6 +35,47 @@ impl Suit {
             Suit::Hearts | Suit::Diamonds => Colour::Red,
         }
     }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Suit::Clubs => '♣',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+            Suit::Spades => '♠',
+        }
+    }
+}
+
+/// `C`, `H`, `D` or `S` - the alternate form (`{:#}`) uses
+/// the unicode suit symbol
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.symbol());
+        }
+
+        let letter = match self {
+            Suit::Clubs => "C",
+            Suit::Hearts => "H",
+            Suit::Diamonds => "D",
+            Suit::Spades => "S",
+        };
+        write!(f, "{}", letter)
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Suit, ParseError> {
+        match s {
+            "C" | "c" | "♣" => Ok(Suit::Clubs),
+            "H" | "h" | "♥" => Ok(Suit::Hearts),
+            "D" | "d" | "♦" => Ok(Suit::Diamonds),
+            "S" | "s" | "♠" => Ok(Suit::Spades),
+            _ => Err(ParseError::InvalidSuit(s.to_string())),
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -46,6 +97,50 @@ pub enum Rank {
     Two,
 }
 
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let token = match self {
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+            Rank::Two => "2",
+        };
+        write!(f, "{}", token)
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Rank, ParseError> {
+        match s {
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "10" | "T" | "t" => Ok(Rank::Ten),
+            "J" | "j" => Ok(Rank::Jack),
+            "Q" | "q" => Ok(Rank::Queen),
+            "K" | "k" => Ok(Rank::King),
+            "A" | "a" => Ok(Rank::Ace),
+            "2" => Ok(Rank::Two),
+            _ => Err(ParseError::InvalidRank(s.to_string())),
+        }
+    }
+}
+
 pub fn get_suit_array() -> [Suit; 4] {
     [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades]
 }
@@ -78,4 +173,33 @@ mod tests {
         assert_eq!(Suit::Diamonds.colour(), Colour::Red);
         assert_eq!(Suit::Spades.colour(), Colour::Black);
     }
+
+    #[test]
+    fn suits_can_be_displayed_and_parsed() {
+        for suit in get_suit_array().iter() {
+            assert_eq!(suit.to_string().parse::<Suit>(), Ok(*suit));
+            assert_eq!(format!("{:#}", suit).parse::<Suit>(), Ok(*suit));
+        }
+
+        assert_eq!(Suit::Hearts.to_string(), "H");
+        assert_eq!(format!("{:#}", Suit::Hearts), "♥");
+        assert_eq!(
+            "X".parse::<Suit>(),
+            Err(ParseError::InvalidSuit("X".to_string()))
+        );
+    }
+
+    #[test]
+    fn ranks_can_be_displayed_and_parsed() {
+        for rank in get_rank_array().iter() {
+            assert_eq!(rank.to_string().parse::<Rank>(), Ok(*rank));
+        }
+
+        assert_eq!(Rank::Ten.to_string(), "10");
+        assert_eq!("T".parse::<Rank>(), Ok(Rank::Ten));
+        assert_eq!(
+            "1".parse::<Rank>(),
+            Err(ParseError::InvalidRank("1".to_string()))
+        );
+    }
 }
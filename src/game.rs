@@ -1,14 +1,30 @@
+mod card_exchange;
 mod game_container;
+mod history;
 mod player;
 #[macro_use]
 mod hands;
 mod comparisons;
+mod joker_solver;
+mod match_container;
+mod move_generation;
+mod notation;
+mod replay;
 mod round;
 mod rulesets;
+mod views;
 
+pub use self::card_exchange::*;
 pub use self::comparisons::*;
 pub use self::game_container::*;
+pub use self::history::*;
 pub use self::hands::*;
+pub use self::joker_solver::*;
+pub use self::match_container::*;
+pub use self::move_generation::*;
+pub use self::notation::*;
 pub use self::player::*;
+pub use self::replay::*;
 pub use self::round::*;
 pub use self::rulesets::*;
+pub use self::views::*;
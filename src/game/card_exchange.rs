@@ -0,0 +1,171 @@
+use super::sort_unplayed_cards;
+use crate::cards::{Card, Rank, Suit};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum ExchangeError {
+    UnknownPlayer,
+    /// cards can only change hands before the first move
+    GameAlreadyStarted,
+    NoExchangeDue,
+    WrongNumberOfCards { expected: usize, given: usize },
+    /// the cards that aren't in the giver's hand
+    PlayerDoesntHaveCard(Vec<Card>),
+    MustGiveHighestCards,
+    /// cards can't be given to the player who holds them
+    SamePlayer,
+}
+
+/// An exchange that a player still has to make
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub from: String,
+    pub to: String,
+    pub count: usize,
+    /// the giver has no choice, and must hand over their best cards
+    pub must_give_highest: bool,
+}
+
+/// Cards that were moved between hands before the first move
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CardTransfer {
+    pub from: String,
+    pub to: String,
+    pub cards: Vec<Card>,
+}
+
+/// The exchanges due at the start of a deal, given the finishing
+/// order of the last one. Each loser gives their highest cards to
+/// the matching winner, who then gives back the same number of
+/// cards of their choice
+pub fn get_exchanges(
+    finishing_order: &[String],
+    card_exchange: [u8; 4]
+) -> Vec<Exchange> {
+    let players = finishing_order.len();
+
+    card_exchange.iter()
+        .enumerate()
+        .take(players / 2)
+        .filter(|(_, &count)| count > 0)
+        .map(|(place, &count)| Exchange {
+            from: finishing_order[players - 1 - place].to_string(),
+            to: finishing_order[place].to_string(),
+            count: count as usize,
+            must_give_highest: true,
+        })
+        .collect()
+}
+
+/// The `count` best cards in a hand, best first - jokers are
+/// counted as the best cards of all
+pub fn get_highest_cards(
+    hand: &[Card],
+    count: usize,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> Vec<Card> {
+    let mut sorted = sort_unplayed_cards(hand, suit_order, rank_order);
+    sorted.truncate(count);
+
+    sorted
+}
+
+/// Check that no card left in the hand beats any of the given cards
+pub fn are_highest_cards(
+    hand: &[Card],
+    cards: &[Card],
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> bool {
+    let highest = get_highest_cards(hand, cards.len(), suit_order, rank_order);
+    let key = |card: &Card| (card.get_rank(), card.get_suit());
+    let mut expected: Vec<_> = highest.iter().map(key).collect();
+    let mut given: Vec<_> = cards.iter().map(key).collect();
+    expected.sort();
+    given.sort();
+
+    expected == given
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+
+    static DEFAULT_RANK_ORDER: [Rank; 13] = [
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+        Rank::Two,
+    ];
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::Standard { deck_id: 0, rank, suit }
+    }
+
+    #[test]
+    fn losers_give_cards_to_winners() {
+        let order = vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+            "e".to_string(),
+        ];
+
+        assert_eq!(
+            get_exchanges(&order, [2, 1, 1, 0]),
+            vec![
+                Exchange {
+                    from: "e".to_string(),
+                    to: "a".to_string(),
+                    count: 2,
+                    must_give_highest: true,
+                },
+                Exchange {
+                    from: "d".to_string(),
+                    to: "b".to_string(),
+                    count: 1,
+                    must_give_highest: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn the_highest_cards_include_jokers() {
+        let hand = vec![
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Two, Suit::Hearts),
+            Card::Joker { deck_id: 0 },
+            card(Rank::Ace, Suit::Spades),
+        ];
+
+        assert_eq!(
+            get_highest_cards(&hand, 2, get_suit_array(), DEFAULT_RANK_ORDER),
+            vec![Card::Joker { deck_id: 0 }, card(Rank::Two, Suit::Hearts)]
+        );
+        assert!(are_highest_cards(
+            &hand,
+            &[card(Rank::Two, Suit::Hearts), Card::Joker { deck_id: 1 }],
+            get_suit_array(),
+            DEFAULT_RANK_ORDER
+        ));
+        assert!(!are_highest_cards(
+            &hand,
+            &[card(Rank::Ace, Suit::Spades)],
+            get_suit_array(),
+            DEFAULT_RANK_ORDER
+        ));
+    }
+}
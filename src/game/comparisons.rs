@@ -1,7 +1,10 @@
-use super::{Hand, TrickType, FlushPrecedence};
+use super::{Hand, TrickType, FlushPrecedence, Ruleset, SubmitError};
 use crate::cards::{Card, PlayedCard, Rank, Suit};
 use std::cmp::Ordering;
 
+/// Whether `new_hand` beats `last_move` - it has to be the same
+/// shape and strictly stronger. This knows nothing of bombs or flush
+/// kickers, so games should use `compare_hands_with_ruleset`
 pub fn compare_hands(
     last_move: Hand,
     new_hand: Hand,
@@ -9,36 +12,22 @@ pub fn compare_hands(
     suit_order: [Suit; 4],
     rank_order: [Rank; 13],
 ) -> bool {
-    let last_cards = last_move.to_cards();
-    let new_cards = new_hand.to_cards();
-
-    if last_cards.len() != new_cards.len() {
-        return false;
-    }
-
-    match last_move {
-        Hand::Single(_)
-        | Hand::Pair(_, _)
-        | Hand::Prial(_, _, _) => {
-            let last_card = get_top_card(
-                last_cards, suit_order, rank_order
-            );
-            let new_card = get_top_card(
-                new_cards, suit_order, rank_order
-            );
-            compare_single(
-                last_card, new_card, suit_order, rank_order
-            ) == Ordering::Greater
-        }
-        Hand::FiveCardTrick(_) => compare_five_cards(
-            last_move,
-            new_hand,
-            suit_order,
-            rank_order,
-            flush_precedence
-        ),
-        _ => false,
-    }
+    HandOrdering::new(flush_precedence, suit_order, rank_order)
+        .compare(new_hand, last_move) == Some(Ordering::Greater)
+}
+
+/// As `compare_hands`, but following all of the ruleset - so a bomb
+/// can beat a smaller hand, and flushes can be decided by kickers
+pub fn compare_hands_with_ruleset(
+    last_move: Hand,
+    new_hand: Hand,
+    ruleset: &Ruleset,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> bool {
+    ruleset.bombs.is_some_and(|b| b.can_bomb(last_move, new_hand))
+        || HandOrdering::from_ruleset(ruleset, suit_order, rank_order)
+            .compare(new_hand, last_move) == Some(Ordering::Greater)
 }
 
 pub fn sort_played_cards(
@@ -46,10 +35,11 @@ pub fn sort_played_cards(
     suit_order: [Suit; 4],
     rank_order: [Rank; 13]
 ) -> Vec<PlayedCard> {
-    let mut sortable_cards = hand.to_owned();
-    sortable_cards.sort_by(
-        |&a, &b| compare_single(a, b, suit_order, rank_order)
+    let ordering = HandOrdering::new(
+        FlushPrecedence::Rank, suit_order, rank_order
     );
+    let mut sortable_cards = hand.to_owned();
+    sortable_cards.sort_by(|&a, &b| ordering.compare_cards(b, a));
     sortable_cards
 }
 
@@ -65,20 +55,6 @@ pub fn sort_unplayed_cards(
     sortable_cards
 }
 
-fn compare_single(
-    last_card: PlayedCard,
-    new_card: PlayedCard,
-    suit_order: [Suit; 4],
-    rank_order: [Rank; 13],
-) -> Ordering {
-    let rank_comparison = compare_rank(last_card, new_card, rank_order);
-
-    match rank_comparison {
-        Ordering::Equal => compare_suits(last_card, new_card, suit_order),
-        x => x,
-    }
-}
-
 fn compare_single_unplayed(
     last_card: Card,
     new_card: Card,
@@ -99,89 +75,158 @@ pub fn compare_five_cards(
     new_hand: Hand,
     suit_order: [Suit; 4],
     rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Result<bool, SubmitError> {
+    match (last_move, new_hand) {
+        (Hand::FiveCardTrick(_), Hand::FiveCardTrick(_)) => {
+            Ok(compare_hands_with_ruleset(
+                last_move, new_hand, ruleset, suit_order, rank_order
+            ))
+        },
+        _ => Err(SubmitError::NotATrick),
+    }
+}
+
+/// Sorts hands by size, then trick type, then the ranks and suits
+/// that decide between hands of that type, most significant first
+pub type StrengthKey = (usize, usize, [usize; 6]);
+
+/// A total order over hands of the same shape, for one set of
+/// suit and rank orders
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HandOrdering {
     flush_precedence: FlushPrecedence,
-) -> bool {
-    let last_trick = match last_move {
-        Hand::FiveCardTrick(x) => x,
-        _ => panic!("unable to get trick"),
-    };
-    let new_trick = match new_hand {
-        Hand::FiveCardTrick(x) => x,
-        _ => panic!("unable to get trick"),
-    };
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    flush_kickers: bool,
+}
 
-    if new_trick.trick_type > last_trick.trick_type {
-        return true;
+impl HandOrdering {
+    pub fn new(
+        flush_precedence: FlushPrecedence,
+        suit_order: [Suit; 4],
+        rank_order: [Rank; 13],
+    ) -> HandOrdering {
+        HandOrdering {
+            flush_precedence,
+            suit_order,
+            rank_order,
+            flush_kickers: false,
+        }
     }
 
-    if new_trick.trick_type < last_trick.trick_type {
-        return false;
+    pub fn from_ruleset(
+        ruleset: &Ruleset,
+        suit_order: [Suit; 4],
+        rank_order: [Rank; 13],
+    ) -> HandOrdering {
+        HandOrdering {
+            flush_kickers: ruleset.flush_kickers,
+            ..HandOrdering::new(
+                ruleset.flush_precedence, suit_order, rank_order
+            )
+        }
     }
 
-    let last_cards = last_move.to_cards();
-    let new_cards = new_hand.to_cards();
+    /// How `a` compares to `b`, or `None` when they can't be played
+    /// over each other - passes, or hands with different numbers of
+    /// cards. Hands whose deciding cards match are `Equal`
+    pub fn compare(&self, a: Hand, b: Hand) -> Option<Ordering> {
+        let size = a.to_cards().len();
+        if size == 0 || size != b.to_cards().len() {
+            return None;
+        }
 
-    let comparison_result = match last_trick.trick_type {
-        TrickType::Straight
-        | TrickType::FiveOfAKind => {
-            let last_card = get_top_card(
-                last_cards, suit_order, rank_order
-            );
-            let new_card = get_top_card(
-                new_cards, suit_order, rank_order
-            );
-            compare_single(
-                last_card, new_card, suit_order, rank_order
-            )
-        },
-        TrickType::Flush
-        | TrickType::StraightFlush => {
-            let last_card = get_top_card(
-                last_cards, suit_order, rank_order
-            );
-            let new_card = get_top_card(
-                new_cards, suit_order, rank_order
-            );
-
-            if flush_precedence == FlushPrecedence::Suit {
-                let rank_comparison = compare_suits(
-                    last_card, new_card, suit_order
-                );
-
-                match rank_comparison {
-                    Ordering::Equal => compare_rank(
-                        last_card, new_card, rank_order
-                    ),
-                    x => x,
-                }
-            } else {
-                compare_single(
-                    last_card, new_card, suit_order, rank_order
-                )
-            }
+        Some(self.get_strength_key(a).cmp(&self.get_strength_key(b)))
+    }
 
-        },
-        TrickType::FullHouse | TrickType::FourOfAKind => {
-            let set_count = if last_trick.trick_type == TrickType::FullHouse {
-                3
-            } else {
-                4
-            };
-
-            let last_card = get_top_of_n(
-                last_cards, set_count, suit_order, rank_order
-            );
-            let new_card = get_top_of_n(
-                new_cards, set_count, suit_order, rank_order
-            );
-
-            compare_single(
-                last_card, new_card, suit_order, rank_order
-            )
+    /// How `a` compares to `b` as singles
+    pub fn compare_cards(&self, a: PlayedCard, b: PlayedCard) -> Ordering {
+        self.get_card_key(a).cmp(&self.get_card_key(b))
+    }
+
+    /// A key that sorts hands by size, then by how hard they are
+    /// to beat
+    pub fn get_strength_key(&self, hand: Hand) -> StrengthKey {
+        let card = match get_deciding_card(
+            hand, self.suit_order, self.rank_order
+        ) {
+            Some(card) => card,
+            None => return (0, 0, [0; 6]),
+        };
+        let (rank, suit) = self.get_card_key(card);
+
+        let trick_type = match hand {
+            Hand::FiveCardTrick(trick) => trick.trick_type,
+            _ => return (hand.to_cards().len(), 0, [rank, suit, 0, 0, 0, 0]),
+        };
+        let is_flush = trick_type == TrickType::Flush
+            || trick_type == TrickType::StraightFlush;
+        let suit_first = is_flush
+            && self.flush_precedence == FlushPrecedence::Suit;
+
+        // a straight flush's run already fixes every rank below the top
+        let has_kickers = trick_type == TrickType::Flush && self.flush_kickers;
+
+        let key = match (suit_first, has_kickers) {
+            (true, true) => {
+                let [r1, r2, r3, r4, r5] = self.get_ranks_high_to_low(hand);
+                [suit, r1, r2, r3, r4, r5]
+            },
+            (false, true) => {
+                let [r1, r2, r3, r4, r5] = self.get_ranks_high_to_low(hand);
+                [r1, r2, r3, r4, r5, suit]
+            },
+            (true, false) => [suit, rank, 0, 0, 0, 0],
+            (false, false) => [rank, suit, 0, 0, 0, 0],
+        };
+
+        (5, trick_type as usize, key)
+    }
+
+    fn get_ranks_high_to_low(&self, hand: Hand) -> [usize; 5] {
+        let mut ranks = [0; 5];
+        for (i, card) in hand.to_cards().into_iter().take(5).enumerate() {
+            ranks[i] = self.get_card_key(card).0;
         }
-    };
+        ranks.sort_by(|a, b| b.cmp(a));
+
+        ranks
+    }
 
-    comparison_result == Ordering::Greater
+    fn get_card_key(&self, card: PlayedCard) -> (usize, usize) {
+        (
+            get_rank_index(card, self.rank_order).unwrap_or(0),
+            get_suit_index(card, self.suit_order).unwrap_or(0),
+        )
+    }
+}
+
+/// The card that decides how strong a hand is when compared
+/// against another hand of the same type
+pub fn get_deciding_card(
+    hand: Hand,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> Option<PlayedCard> {
+    let cards = hand.to_cards();
+
+    match hand {
+        Hand::Pass => None,
+        Hand::FiveCardTrick(trick) => match trick.trick_type {
+            TrickType::FullHouse => Some(
+                get_top_of_n(cards, 3, suit_order, rank_order)
+            ),
+            TrickType::FourOfAKind => Some(
+                get_top_of_n(cards, 4, suit_order, rank_order)
+            ),
+            TrickType::Straight | TrickType::StraightFlush => Some(
+                get_straight_top_card(cards, suit_order, rank_order)
+            ),
+            _ => Some(get_top_card(cards, suit_order, rank_order)),
+        },
+        _ => Some(get_top_card(cards, suit_order, rank_order)),
+    }
 }
 
 fn get_top_card(
@@ -196,6 +241,27 @@ fn get_top_card(
     ).first().expect("no cards found")
 }
 
+/// The card at the top of a straight's run, going up the rank
+/// order and wrapping from the highest rank back to the lowest -
+/// so A-2-3-4-5 is topped by its 5
+fn get_straight_top_card(
+    cards: Vec<PlayedCard>,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+) -> PlayedCard {
+    let next_rank = |card: PlayedCard| {
+        get_rank_index(card, rank_order)
+            .map(|i| rank_order[(i + 1) % rank_order.len()])
+    };
+
+    cards.iter()
+        .find(|&&card| {
+            !cards.iter().any(|c| Some(c.get_rank()) == next_rank(card))
+        })
+        .cloned()
+        .unwrap_or_else(|| get_top_card(cards, suit_order, rank_order))
+}
+
 fn get_top_of_n(
     cards: Vec<PlayedCard>,
     n: usize,
@@ -220,20 +286,6 @@ fn get_top_of_n(
     get_top_card(valid_cards, suits_order, rank_order)
 }
 
-fn compare_suits(card1: PlayedCard, card2: PlayedCard, suit_order: [Suit; 4]) -> Ordering {
-    let c1_i = get_suit_index(card1, suit_order);
-    let c2_i = get_suit_index(card2, suit_order);
-
-    c2_i.cmp(&c1_i)
-}
-
-fn compare_rank(card1: PlayedCard, card2: PlayedCard, rank_order: [Rank; 13]) -> Ordering {
-    let c1_i = get_rank_index(card1, rank_order);
-    let c2_i = get_rank_index(card2, rank_order);
-
-    c2_i.cmp(&c1_i)
-}
-
 fn get_suit_index(card: PlayedCard, suit_order: [Suit; 4]) -> Option<usize> {
     suit_order.iter().position(|&x| x == card.get_suit())
 }
@@ -283,6 +335,9 @@ mod tests {
     use super::*;
     use crate::cards::{PlayedCard, Rank, Suit};
     use crate::game::hands::*;
+    use crate::game::{
+        HandRules, StraightRule, DEFAULT_HAND_RULES, DEFAULT_RULESET,
+    };
 
     static DEFAULT_SUIT_ORDER: [Suit; 4] =
         [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades];
@@ -883,4 +938,200 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn comparing_five_cards_requires_tricks() {
+        let hand1_cards = [
+            PlayedCard::new(Rank::Three, Suit::Spades, false),
+            PlayedCard::new(Rank::Four, Suit::Spades, false),
+            PlayedCard::new(Rank::Five, Suit::Spades, false),
+            PlayedCard::new(Rank::Six, Suit::Spades, false),
+            PlayedCard::new(Rank::Seven, Suit::Spades, false),
+        ];
+        let hand1 = build_fct!(StraightFlush, hand1_cards).unwrap();
+        let hand2 = Hand::Single(
+            PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        );
+
+        assert_eq!(
+            compare_five_cards(
+                hand1,
+                hand2,
+                DEFAULT_SUIT_ORDER,
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET,
+            ),
+            Err(SubmitError::NotATrick)
+        );
+        assert_eq!(
+            compare_five_cards(
+                hand2,
+                hand1,
+                DEFAULT_SUIT_ORDER,
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET,
+            ),
+            Err(SubmitError::NotATrick)
+        );
+    }
+
+    #[test]
+    fn wrapped_straights_rank_by_the_top_of_their_run() {
+        let build = |cards: [&str; 5]| {
+            Hand::build_with_rules(
+                cards.iter().map(|c| c.parse().unwrap()).collect(),
+                &HandRules {
+                    straight_rule: StraightRule::WrapAround,
+                    ..DEFAULT_HAND_RULES
+                }
+            ).unwrap()
+        };
+        let wheel = build(["AS", "2S", "3C", "4C", "5C"]);
+        let two_low = build(["2C", "3C", "4C", "5C", "6C"]);
+        let lowest = build(["3C", "4C", "5C", "6C", "7C"]);
+
+        assert!(compare_hands(
+            wheel,
+            two_low,
+            FlushPrecedence::Rank,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+        ));
+        assert!(compare_hands(
+            two_low,
+            lowest,
+            FlushPrecedence::Rank,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+        ));
+        assert_eq!(
+            get_deciding_card(wheel, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER),
+            Some(PlayedCard::new(Rank::Five, Suit::Clubs, false))
+        );
+
+        let mut reversed_suits = DEFAULT_SUIT_ORDER;
+        let mut reversed_ranks = DEFAULT_RANK_ORDER;
+        reversed_suits.reverse();
+        reversed_ranks.reverse();
+        assert_eq!(
+            get_deciding_card(lowest, reversed_suits, reversed_ranks),
+            Some(PlayedCard::new(Rank::Three, Suit::Clubs, false))
+        );
+    }
+
+    #[test]
+    fn hand_ordering_compares_hands_of_the_same_shape() {
+        let ordering = HandOrdering::new(
+            FlushPrecedence::Rank, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+        let hand = |cards: &str| cards.parse::<Hand>().unwrap();
+
+        assert_eq!(
+            ordering.compare(hand("3C 3H"), hand("3D 3S")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            ordering.compare(hand("2S"), hand("AS")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            ordering.compare(hand("9C 9H 9D"), hand("9D 9C 9H")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(ordering.compare(hand("3C"), hand("3D 3S")), None);
+        assert_eq!(ordering.compare(Hand::Pass, Hand::Pass), None);
+    }
+
+    #[test]
+    fn hand_ordering_follows_the_flush_precedence() {
+        let hand = |cards: &str| cards.parse::<Hand>().unwrap();
+        let clubs = hand("4C 6C 8C TC AC");
+        let spades = hand("3S 5S 7S 9S JS");
+
+        let by_rank = HandOrdering::new(
+            FlushPrecedence::Rank, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+        let by_suit = HandOrdering::new(
+            FlushPrecedence::Suit, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+
+        assert_eq!(by_rank.compare(clubs, spades), Some(Ordering::Greater));
+        assert_eq!(by_suit.compare(clubs, spades), Some(Ordering::Less));
+        assert!(
+            by_suit.get_strength_key(clubs) < by_suit.get_strength_key(spades)
+        );
+    }
+
+    #[test]
+    fn sorted_cards_follow_the_hand_ordering() {
+        let ordering = HandOrdering::new(
+            FlushPrecedence::Rank, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+        let cards: Vec<PlayedCard> = ["5D", "2C", "5C", "KS"].iter()
+            .map(|c| c.parse().unwrap())
+            .collect();
+
+        let sorted = sort_played_cards(
+            &cards, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+
+        assert!(sorted.windows(2).all(|pair| {
+            ordering.compare_cards(pair[0], pair[1]) == Ordering::Greater
+        }));
+    }
+
+    #[test]
+    fn flush_kickers_separate_flushes_with_the_same_top_card() {
+        let hand = |cards: &str| cards.parse::<Hand>().unwrap();
+        let low_kickers = hand("3C 5C 7C 9C AC");
+        let high_kickers = hand("4C 5C 7C 9C AC");
+        let kickers = HandOrdering::from_ruleset(
+            &Ruleset {
+                reversals_enabled: false,
+                flush_kickers: true,
+                ..Ruleset::default()
+            },
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+        );
+        let top_card_only = HandOrdering::new(
+            FlushPrecedence::Rank, DEFAULT_SUIT_ORDER, DEFAULT_RANK_ORDER
+        );
+
+        assert_eq!(
+            top_card_only.compare(high_kickers, low_kickers),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            kickers.compare(high_kickers, low_kickers),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            kickers.compare(low_kickers, hand("3C 5C 7C 9C AC")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            kickers.compare(hand("3H 5H 7H 9H AH"), low_kickers),
+            Some(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn comparing_five_cards_follows_flush_kickers() {
+        let hand = |cards: &str| cards.parse::<Hand>().unwrap();
+        let low_kickers = hand("3C 5C 7C 9C AC");
+        let high_kickers = hand("4C 5C 7C 9C AC");
+        let compare = |ruleset: &Ruleset| compare_five_cards(
+            low_kickers,
+            high_kickers,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+
+        assert_eq!(compare(&DEFAULT_RULESET), Ok(false));
+        assert_eq!(
+            compare(&Ruleset { flush_kickers: true, ..DEFAULT_RULESET }),
+            Ok(true)
+        );
+    }
 }
@@ -1,32 +1,68 @@
 use super::{
+    CardTransfer,
+    ExchangeError,
     Player,
     Round,
     SubmitError,
     Hand,
+    Turn,
+    PlayerView,
+    SpectatorView,
     sort_unplayed_cards,
     Ruleset,
-    compare_hands,
-    FlushPrecedence
+    compare_hands_with_ruleset,
+    get_weakest_beating_hand,
+    FlushPrecedence,
 };
 use crate::cards::{
     get_rank_array,
+    Card,
     Deck,
     PlayedCard,
     Suit,
     Rank,
 };
-use crate::ai::get_move;
+use crate::ai::{GreedyLowest, Strategy, StrategyRegistry};
+use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Why a computer strategy couldn't be seated or play its move
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum SeatError {
+    /// the next player has no computer strategy seated
+    NoStrategySeated,
+    /// the strategy name isn't in the registry
+    UnknownStrategy(String),
+    /// the player is unknown, or the move itself was rejected
+    Submit(SubmitError),
+}
+
+impl From<SubmitError> for SeatError {
+    fn from(error: SubmitError) -> SeatError {
+        SeatError::Submit(error)
+    }
+}
+
 #[wasm_bindgen]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Game {
     num_decks: u8,
     num_jokers: u8,
     round: Round,
     winners: Vec<String>,
     ruleset: Ruleset,
+    /// the seed the deck was shuffled with - unknown for games
+    /// saved before seeds were recorded, or dealt some other way
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    history: Vec<Turn>,
+    #[serde(default)]
+    transfers: Vec<CardTransfer>,
+    /// names of the computer strategies playing for each player
+    #[serde(default)]
+    strategies: BTreeMap<String, String>,
 }
 
 impl Game {
@@ -36,11 +72,31 @@ impl Game {
         player_ids: &[String],
         suit_order: [Suit; 4],
         ruleset: Ruleset
+    ) -> Game {
+        Self::new_with_seed(
+            num_decks,
+            num_jokers,
+            player_ids,
+            suit_order,
+            ruleset,
+            rand::random()
+        )
+    }
+
+    /// Deal a game from a known seed - the same seed, players
+    /// and settings will always produce the same deal
+    pub fn new_with_seed(
+        num_decks: u8,
+        num_jokers: u8,
+        player_ids: &[String],
+        suit_order: [Suit; 4],
+        ruleset: Ruleset,
+        seed: u64,
     ) -> Game {
         let rank_order = get_rank_array();
 
         let mut deck = Deck::new(num_decks, num_jokers);
-        deck.shuffle();
+        deck.shuffle_with_seed(seed);
         let cards = deck.deal(player_ids.len() as u8);
 
         let players: Vec<Player> = cards
@@ -48,7 +104,7 @@ impl Game {
             .zip(player_ids)
             .map(|(c, id)| {
                 let mut player_hand = sort_unplayed_cards(
-                    &c, suit_order, rank_order
+                    c, suit_order, rank_order
                 );
                 player_hand.reverse();
 
@@ -59,13 +115,32 @@ impl Game {
             })
             .collect();
 
+        Self::from_players(
+            num_decks,
+            num_jokers,
+            players,
+            suit_order,
+            ruleset,
+            Some(seed)
+        )
+    }
+
+    /// Start a game from hands that have already been dealt
+    pub fn from_players(
+        num_decks: u8,
+        num_jokers: u8,
+        players: Vec<Player>,
+        suit_order: [Suit; 4],
+        ruleset: Ruleset,
+        seed: Option<u64>,
+    ) -> Game {
         let round = Round::new(
-            players.clone(),
+            players,
             None,
             None,
             None,
             suit_order,
-            rank_order,
+            get_rank_array(),
             ruleset
         );
 
@@ -74,7 +149,11 @@ impl Game {
             num_jokers,
             round,
             winners: vec!(),
-            ruleset
+            ruleset,
+            seed,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         }
     }
 
@@ -83,15 +162,22 @@ impl Game {
         player_id: &str,
         player_move: Vec<PlayedCard>,
     ) -> Result<(), SubmitError> {
-        match self.round.submit_move(player_id, player_move) {
+        match self.round.submit_move(player_id, player_move.clone()) {
             Ok(new_round) => {
                 let player = new_round.get_player(player_id)
                     .unwrap();
-                if player.get_hand().is_empty()
+                let went_out = player.get_hand().is_empty()
                     && !self.winners
-                            .contains(&player_id.to_string()) {
+                            .contains(&player_id.to_string());
+                if went_out {
                     self.winners.push(player_id.to_string());
                 }
+                self.record_turn(
+                    player_id,
+                    player_move,
+                    &new_round,
+                    went_out
+                );
                 self.round = new_round;
                 Ok(())
             },
@@ -99,6 +185,134 @@ impl Game {
         }
     }
 
+    pub fn get_history(&self) -> Vec<Turn> {
+        self.history.clone()
+    }
+
+    /// Cards moved between hands before the first move
+    pub fn get_transfers(&self) -> Vec<CardTransfer> {
+        self.transfers.clone()
+    }
+
+    /// Move cards from one hand to another - only allowed
+    /// before the first move of the game. A card from a deck the
+    /// giver doesn't hold it from is taken from another deck, and
+    /// the transfer records the cards that actually moved
+    pub fn transfer_cards(
+        &mut self,
+        from: &str,
+        to: &str,
+        cards: &[Card]
+    ) -> Result<(), ExchangeError> {
+        if !self.history.is_empty() {
+            return Err(ExchangeError::GameAlreadyStarted);
+        }
+        if from == to {
+            return Err(ExchangeError::SamePlayer);
+        }
+
+        let mut giver = self.get_player(from)
+            .ok_or(ExchangeError::UnknownPlayer)?;
+        let mut receiver = self.get_player(to)
+            .ok_or(ExchangeError::UnknownPlayer)?;
+
+        let moved_cards = giver.remove_cards(cards)
+            .map_err(ExchangeError::PlayerDoesntHaveCard)?;
+        receiver.add_cards(&moved_cards);
+
+        let suit_order = self.round.get_suit_order();
+        let rank_order = self.round.get_rank_order();
+        let players = self.round.get_players()
+            .into_iter()
+            .map(|p| {
+                let player = if p.get_id() == from {
+                    giver.clone()
+                } else if p.get_id() == to {
+                    receiver.clone()
+                } else {
+                    p
+                };
+                let mut hand = sort_unplayed_cards(
+                    &player.get_hand(), suit_order, rank_order
+                );
+                hand.reverse();
+
+                Player::new(player.get_id().to_string(), hand)
+            })
+            .collect();
+
+        let starting_player = self.round.get_chosen_starting_player();
+        self.round = Round::new(
+            players,
+            None,
+            None,
+            None,
+            suit_order,
+            rank_order,
+            self.ruleset
+        );
+        self.round.set_starting_player(starting_player);
+        self.transfers.push(CardTransfer {
+            from: from.to_string(),
+            to: to.to_string(),
+            cards: moved_cards,
+        });
+
+        Ok(())
+    }
+
+    /// Choose who makes the first move, for starting rules that
+    /// don't go by the cards - has no effect once the game has started
+    pub fn set_starting_player(
+        &mut self,
+        player_id: &str
+    ) -> Result<(), SubmitError> {
+        if self.get_player(player_id).is_none() {
+            return Err(SubmitError::UnknownPlayer);
+        }
+
+        self.round.set_starting_player(Some(player_id.to_string()));
+
+        Ok(())
+    }
+
+    pub fn get_round(&self) -> &Round {
+        &self.round
+    }
+
+    pub fn get_player_ids(&self) -> Vec<String> {
+        self.round.get_players()
+            .iter()
+            .map(|p| p.get_id().to_string())
+            .collect()
+    }
+
+    pub fn get_num_decks(&self) -> u8 {
+        self.num_decks
+    }
+
+    pub fn get_num_jokers(&self) -> u8 {
+        self.num_jokers
+    }
+
+    pub fn get_ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    /// The suit order the game was dealt with, before any reversals
+    pub fn get_initial_suit_order(&self) -> [Suit; 4] {
+        let mut suit_order = self.round.get_suit_order();
+        let reversals = self.history.iter()
+            .filter(|t| t.reversed)
+            .count();
+
+        if reversals % 2 == 1 {
+            suit_order.reverse();
+        }
+
+        suit_order
+    }
+
     pub fn get_player(&self, id: &str) -> Option<Player> {
         self.round.get_player(id)
     }
@@ -111,14 +325,77 @@ impl Game {
         self.round.get_last_move()
     }
 
-    pub fn suggest_move(&self, id: &str) -> Option<Vec<PlayedCard>> {
-        get_move(
+    pub fn suggest_move(
+        &self,
+        id: &str
+    ) -> Result<Vec<PlayedCard>, SubmitError> {
+        GreedyLowest.choose_move(&self.view_for(id)?)
+    }
+
+    /// Have a named strategy from a `StrategyRegistry` play for
+    /// the player
+    pub fn seat_strategy(
+        &mut self,
+        id: &str,
+        strategy_name: &str
+    ) -> Result<(), SeatError> {
+        self.get_player(id).ok_or(SubmitError::UnknownPlayer)?;
+        self.strategies.insert(id.to_string(), strategy_name.to_string());
+
+        Ok(())
+    }
+
+    pub fn get_seated_strategy(&self, id: &str) -> Option<String> {
+        self.strategies.get(id).cloned()
+    }
+
+    /// Let the strategy seated for the next player choose and play
+    /// their move, returning the cards played
+    pub fn play_seated_move(
+        &mut self,
+        registry: &StrategyRegistry
+    ) -> Result<Vec<PlayedCard>, SeatError> {
+        let id = self.get_next_player().ok_or(SubmitError::GameOver)?;
+        let strategy_name = self.strategies.get(&id)
+            .ok_or(SeatError::NoStrategySeated)?;
+        let strategy = registry.get(strategy_name)
+            .ok_or_else(|| {
+                SeatError::UnknownStrategy(strategy_name.clone())
+            })?;
+
+        let cards = strategy.choose_move(&self.view_for(&id)?)?;
+        self.play_move(&id, cards.clone())?;
+
+        Ok(cards)
+    }
+
+    /// Every move the player could make right now, weakest first
+    pub fn legal_moves(
+        &self,
+        id: &str
+    ) -> Result<Vec<Vec<PlayedCard>>, SubmitError> {
+        self.round.legal_moves(id)
+    }
+
+    /// The weakest hand the selected cards can make that beats the
+    /// last move, with the engine choosing what any jokers stand for
+    pub fn choose_hand(&self, cards: &[Card]) -> Option<Hand> {
+        get_weakest_beating_hand(
+            cards,
             self.get_last_move(),
-            self.get_player(&id),
             self.round.get_suit_order(),
             self.round.get_rank_order(),
+            &self.ruleset,
         )
-            
+    }
+
+    /// The game as seen by one player, without opponents' cards
+    pub fn view_for(&self, id: &str) -> Result<PlayerView, SubmitError> {
+        PlayerView::from_game(self, id)
+    }
+
+    pub fn spectator_view(&self) -> SpectatorView {
+        SpectatorView::from_game(self)
     }
 
     pub fn get_winners(&self) -> Vec<String> {
@@ -128,41 +405,11 @@ impl Game {
     pub fn check_move(
         &self,
         hand: Vec<PlayedCard>) -> bool {
-
-        let new_hand_option = Hand::build(hand.clone());
-        let last_move_option = self.round.get_last_move();
-
-        if new_hand_option.is_none() {
-            return false;
-        }
-
-        if last_move_option.is_none() {
-
-            let lowest_card = PlayedCard::new(
-                self.round.get_rank_order()[0],
-                self.round.get_suit_order()[0],
-                false
-            );
-
-            return hand.contains(&lowest_card);
-        }
-
-        let new_hand = new_hand_option.expect("invalid hand");
-        let last_move = last_move_option.expect("no last move");
-
-        if last_move == Hand::Pass {
-            return true;
-        }
-
-        compare_hands(
-            last_move,
-            new_hand,
-            self.ruleset.flush_precedence,
-            self.round.get_suit_order(),
-            self.round.get_rank_order()
-        )
+        self.round.check_move(&hand).is_ok()
     }
 
+    /// Checks a move without a game, under the default rules. The
+    /// opening move has to hold the lowest card of the orders
     pub fn check_move_m(
         hand: Vec<PlayedCard>,
         last_move_option: Option<Hand>,
@@ -170,22 +417,45 @@ impl Game {
         rank_order: [Rank; 13],
         flush_precedence: FlushPrecedence,
     ) -> bool {
+        Self::check_move_with_ruleset(
+            hand,
+            last_move_option,
+            Some(PlayedCard::new(rank_order[0], suit_order[0], false)),
+            suit_order,
+            rank_order,
+            &Ruleset { flush_precedence, ..Ruleset::default() }
+        )
+    }
+
+    /// Checks a move without a game, under the hand rules and
+    /// comparisons of `ruleset`. `required_first_card` is the card
+    /// the opening move has to hold, if any
+    pub fn check_move_with_ruleset(
+        hand: Vec<PlayedCard>,
+        last_move_option: Option<Hand>,
+        required_first_card: Option<PlayedCard>,
+        suit_order: [Suit; 4],
+        rank_order: [Rank; 13],
+        ruleset: &Ruleset,
+    ) -> bool {
 
-        let new_hand_option = Hand::build(hand.clone());
+        let new_hand_option = Hand::build_with_rules(
+            hand.clone(),
+            &ruleset.hand_rules
+        );
 
         if new_hand_option.is_none() {
             return false;
         }
 
         if last_move_option.is_none() {
-
-            let lowest_card = PlayedCard::new(
-                rank_order[0],
-                suit_order[0],
-                false
-            );
-
-            return hand.contains(&lowest_card);
+            return match required_first_card {
+                Some(card) => hand.iter().any(|c| {
+                    c.get_rank() == card.get_rank()
+                        && c.get_suit() == card.get_suit()
+                }),
+                None => !hand.is_empty(),
+            };
         }
 
         let new_hand = new_hand_option.expect("invalid hand");
@@ -195,10 +465,10 @@ impl Game {
             return true;
         }
 
-        compare_hands(
+        compare_hands_with_ruleset(
             last_move,
             new_hand,
-            flush_precedence,
+            ruleset,
             suit_order,
             rank_order
         )
@@ -212,19 +482,47 @@ impl Game {
         self.round.get_rank_order()
     }
 
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn record_turn(
+        &mut self,
+        player_id: &str,
+        player_move: Vec<PlayedCard>,
+        new_round: &Round,
+        went_out: bool
+    ) {
+        self.history.push(Turn {
+            turn: self.history.len() + 1,
+            player_id: player_id.to_string(),
+            hand: Hand::build_with_rules(
+                player_move,
+                &self.ruleset.hand_rules
+            ).unwrap_or(Hand::Pass),
+            cleared_table: new_round.was_table_cleared(),
+            reversed: new_round.was_reversed(),
+            went_out,
+        });
+    }
+
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cards::*;
-    use crate::game::FlushPrecedence;
-
-    const DEFAULT_RULESET: Ruleset = Ruleset{
-        reversals_enabled: true,
-        flush_precedence: FlushPrecedence::Rank,
+    use crate::game::{
+        BombRule,
+        FlushPrecedence,
+        HandRules,
+        StartingRule,
+        StraightRule,
+        DEFAULT_HAND_RULES,
+        DEFAULT_RULESET,
     };
-
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn it_allows_retrieving_a_player_by_id() {
@@ -330,7 +628,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!(),
-            ruleset: DEFAULT_RULESET 
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -395,7 +697,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!(),
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -408,7 +714,7 @@ mod tests {
 
         let _ = game.play_move("b", hand);
 
-        assert!(game.get_winners().first().is_none());
+        assert!(game.get_winners().is_empty());
     }
 
     #[test]
@@ -454,7 +760,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!["c".to_string()],
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![];
@@ -507,7 +817,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!["c".to_string()],
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -526,6 +840,595 @@ mod tests {
         );
     }
 
+    #[test]
+    fn games_with_the_same_seed_are_dealt_the_same() {
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let game1 = Game::new_with_seed(
+            1, 2, &ids, get_suit_array(), DEFAULT_RULESET, 1234
+        );
+        let game2 = Game::new_with_seed(
+            1, 2, &ids, get_suit_array(), DEFAULT_RULESET, 1234
+        );
+
+        for id in ids.iter() {
+            assert_eq!(
+                game1.get_player(id).unwrap().get_hand(),
+                game2.get_player(id).unwrap().get_hand()
+            );
+        }
+        assert_eq!(game1.get_next_player(), game2.get_next_player());
+        assert_eq!(game1.get_seed(), Some(1234));
+    }
+
+    #[test]
+    fn a_seed_always_deals_the_same_hands() {
+        let ids = [
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 1234
+        );
+        let hand = |id: &str| -> Vec<String> {
+            game.get_player(id).unwrap().get_hand().iter()
+                .map(|c| c.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            hand("a").join(" "),
+            "5S 6C 6H 8D 9S 10H 10D JH JS QS KS AH AS"
+        );
+        assert_eq!(
+            hand("d").join(" "),
+            "3C 3H 3D 4C 5C 5D 8C 8H 9C QH KH 2C 2D"
+        );
+    }
+
+    #[test]
+    fn games_with_different_seeds_are_dealt_differently() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game1 = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 1
+        );
+        let game2 = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 2
+        );
+
+        assert_ne!(
+            game1.get_player("a").unwrap().get_hand(),
+            game2.get_player("a").unwrap().get_hand()
+        );
+    }
+
+    #[test]
+    fn moves_are_recorded_in_the_history() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 7
+        );
+
+        let first_player = game.get_next_player().unwrap();
+        let hand = vec![
+            PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        ];
+        game.play_move(&first_player, hand).unwrap();
+
+        let second_player = game.get_next_player().unwrap();
+        game.play_move(&second_player, vec![]).unwrap();
+
+        let history = game.get_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].turn, 1);
+        assert_eq!(history[0].player_id, first_player);
+        assert_eq!(
+            history[0].hand,
+            Hand::Single(PlayedCard::new(Rank::Three, Suit::Clubs, false))
+        );
+        assert_eq!(history[1].turn, 2);
+        assert_eq!(history[1].player_id, second_player);
+        assert_eq!(history[1].hand, Hand::Pass);
+        assert!(history[1].cleared_table);
+    }
+
+    #[test]
+    fn invalid_moves_are_not_recorded() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 7
+        );
+
+        let first_player = game.get_next_player().unwrap();
+        let _ = game.play_move(&first_player, vec![]);
+
+        assert!(game.get_history().is_empty());
+    }
+
+    #[test]
+    fn history_records_when_a_player_goes_out() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            }
+        ];
+        let b_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+        ];
+
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+
+        let players = vec![player_a, player_b];
+        let round = Round::new(
+            players,
+            Some("b".to_string()),
+            Some(Hand::Pass),
+            Some("a".to_string()),
+            get_suit_array(),
+            get_rank_array(),
+            DEFAULT_RULESET
+        );
+
+        let mut game = Game{
+            num_decks: 1,
+            num_jokers: 1,
+            round,
+            winners: vec!(),
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
+        };
+
+        let hand = vec![
+            PlayedCard::new(
+                Rank::Three,
+                Suit::Clubs,
+                false,
+            )
+        ];
+
+        game.play_move("b", hand).unwrap();
+
+        let turn = game.get_history().pop().unwrap();
+        assert!(turn.went_out);
+        assert!(!turn.reversed);
+    }
+
+    #[test]
+    fn history_records_reversals() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Hearts,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Diamonds,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Spades,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Five,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Clubs,
+            },
+        ];
+        let b_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+        ];
+
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+
+        let players = vec![player_a, player_b];
+        let round = Round::new(
+            players,
+            Some("a".to_string()),
+            Some(Hand::Pass),
+            Some("b".to_string()),
+            get_suit_array(),
+            get_rank_array(),
+            DEFAULT_RULESET
+        );
+
+        let mut game = Game{
+            num_decks: 1,
+            num_jokers: 0,
+            round,
+            winners: vec!(),
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
+        };
+
+        let hand = vec![
+            PlayedCard::new(Rank::Four, Suit::Clubs, false),
+            PlayedCard::new(Rank::Four, Suit::Hearts, false),
+            PlayedCard::new(Rank::Four, Suit::Diamonds, false),
+            PlayedCard::new(Rank::Four, Suit::Spades, false),
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+        ];
+
+        game.play_move("a", hand).unwrap();
+
+        let turn = game.get_history().pop().unwrap();
+        assert!(turn.reversed);
+        assert!(!turn.went_out);
+    }
+
+    #[test]
+    fn history_records_a_trick_won_by_a_player_who_went_out() {
+        let card = |rank, suit| Card::Standard { deck_id: 0, rank, suit };
+        let players = vec![
+            Player::new("a".to_string(), vec![card(Rank::Two, Suit::Spades)]),
+            Player::new("b".to_string(), vec![
+                card(Rank::Three, Suit::Clubs),
+                card(Rank::Four, Suit::Clubs),
+            ]),
+            Player::new("c".to_string(), vec![
+                card(Rank::Five, Suit::Clubs),
+                card(Rank::Six, Suit::Clubs),
+            ]),
+        ];
+        let round = Round::new(
+            players,
+            Some("a".to_string()),
+            Some(Hand::Pass),
+            Some("a".to_string()),
+            get_suit_array(),
+            get_rank_array(),
+            DEFAULT_RULESET
+        );
+        let mut game = Game{
+            num_decks: 1,
+            num_jokers: 0,
+            round,
+            winners: vec!(),
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
+        };
+
+        game.play_move("a", vec![
+            PlayedCard::new(Rank::Two, Suit::Spades, false)
+        ]).unwrap();
+        game.play_move("b", vec![]).unwrap();
+        game.play_move("c", vec![]).unwrap();
+
+        let cleared: Vec<bool> = game.get_history().iter()
+            .map(|t| t.cleared_table)
+            .collect();
+        assert_eq!(cleared, vec![false, false, true]);
+        assert!(game.get_history().iter().all(|t| !t.reversed));
+        assert_eq!(game.get_next_player(), Some("b".to_string()));
+    }
+
+    #[test]
+    fn suggesting_a_move_for_an_unknown_player_is_an_error() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 7
+        );
+
+        assert_eq!(
+            game.suggest_move("z"),
+            Err(SubmitError::UnknownPlayer)
+        );
+    }
+
+    #[test]
+    fn every_legal_move_can_be_played() {
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let game = Game::new_with_seed(
+            1, 1, &ids, get_suit_array(), DEFAULT_RULESET, 11
+        );
+        let player = game.get_next_player().unwrap();
+
+        let moves = game.legal_moves(&player).unwrap();
+
+        assert!(!moves.is_empty());
+        for cards in moves {
+            let mut next = game.clone();
+            assert!(next.play_move(&player, cards).is_ok());
+        }
+    }
+
+    #[test]
+    fn the_engine_chooses_what_a_joker_stands_for() {
+        let players = vec![
+            Player::new("a".to_string(), vec![
+                Card::Standard {
+                    deck_id: 0,
+                    rank: Rank::Three,
+                    suit: Suit::Clubs,
+                },
+                Card::Joker { deck_id: 0 },
+            ]),
+            Player::new("b".to_string(), vec![
+                Card::Standard {
+                    deck_id: 0,
+                    rank: Rank::Four,
+                    suit: Suit::Clubs,
+                },
+            ]),
+        ];
+        let game = Game::from_players(
+            1, 1, players, get_suit_array(), DEFAULT_RULESET, None
+        );
+        let cards = game.get_player("a").unwrap().get_hand();
+
+        let hand = game.choose_hand(&cards).unwrap();
+
+        assert_eq!(
+            hand,
+            Hand::Pair(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false),
+                PlayedCard::new(Rank::Three, Suit::Clubs, true),
+            )
+        );
+    }
+
+    #[test]
+    fn cards_can_only_be_transferred_before_the_first_move() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 21
+        );
+        let card = *game.get_player("a").unwrap().get_hand().last().unwrap();
+
+        game.transfer_cards("a", "b", &[card]).unwrap();
+        assert_eq!(game.get_player("a").unwrap().get_card_count(), 25);
+        assert!(game.get_player("b").unwrap().has_card(card));
+        assert_eq!(
+            game.transfer_cards("a", "b", &[card]),
+            Err(ExchangeError::PlayerDoesntHaveCard(vec![card]))
+        );
+
+        let player = game.get_next_player().unwrap();
+        let cards = game.suggest_move(&player).unwrap();
+        game.play_move(&player, cards).unwrap();
+
+        assert_eq!(
+            game.transfer_cards("b", "a", &[card]),
+            Err(ExchangeError::GameAlreadyStarted)
+        );
+    }
+
+    #[test]
+    fn cards_cant_be_transferred_to_the_player_holding_them() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 21
+        );
+        let hand = game.get_player("a").unwrap().get_hand();
+
+        assert_eq!(
+            game.transfer_cards("a", "a", &hand[..2]),
+            Err(ExchangeError::SamePlayer)
+        );
+        assert_eq!(game.get_player("a").unwrap().get_hand(), hand);
+        assert!(game.get_transfers().is_empty());
+    }
+
+    #[test]
+    fn transfers_take_the_copy_of_a_card_that_was_named() {
+        let card = |deck_id| Card::Standard {
+            deck_id,
+            rank: Rank::Four,
+            suit: Suit::Diamonds,
+        };
+        let players = vec![
+            Player::new("a".to_string(), vec![card(0), card(1)]),
+            Player::new("b".to_string(), vec![
+                Card::Standard{deck_id: 0, rank: Rank::Three, suit: Suit::Clubs},
+            ]),
+        ];
+        let mut game = Game::from_players(
+            2, 0, players, get_suit_array(), DEFAULT_RULESET, None
+        );
+
+        game.transfer_cards("a", "b", &[card(1)]).unwrap();
+        assert_eq!(game.get_player("a").unwrap().get_hand(), vec![card(0)]);
+        assert_eq!(game.get_transfers()[0].cards, vec![card(1)]);
+
+        // a copy that isn't held is taken from another deck
+        game.transfer_cards("a", "b", &[card(1)]).unwrap();
+        assert!(game.get_player("a").unwrap().get_hand().is_empty());
+        assert_eq!(game.get_transfers()[1].cards, vec![card(0)]);
+    }
+
+    #[test]
+    fn check_move_follows_the_starting_rule() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::DealersLeft,
+            ..DEFAULT_RULESET
+        };
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 21
+        );
+        game.set_starting_player("b").unwrap();
+
+        let card = game.get_player("b").unwrap().get_hand()[0];
+        let cards = vec![PlayedCard::new(
+            card.get_rank().unwrap(),
+            card.get_suit().unwrap(),
+            false
+        )];
+
+        assert_eq!(game.get_next_player(), Some("b".to_string()));
+        assert!(game.check_move(cards.clone()));
+        assert!(!game.check_move(vec![]));
+        assert_eq!(
+            game.set_starting_player("z"),
+            Err(SubmitError::UnknownPlayer)
+        );
+        game.play_move("b", cards).unwrap();
+    }
+
+    #[test]
+    fn a_game_can_start_without_the_lowest_card() {
+        let players = vec![
+            Player::new("a".to_string(), vec![
+                Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Clubs},
+                Card::Standard{deck_id: 0, rank: Rank::Ace, suit: Suit::Spades},
+            ]),
+            Player::new("b".to_string(), vec![
+                Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Spades},
+                Card::Standard{deck_id: 0, rank: Rank::King, suit: Suit::Hearts},
+            ]),
+        ];
+        let game = Game::from_players(
+            1, 0, players, get_suit_array(), DEFAULT_RULESET, None
+        );
+        let lowest_card = PlayedCard::new(Rank::Four, Suit::Spades, false);
+
+        assert_eq!(game.get_next_player(), Some("b".to_string()));
+        assert!(game.check_move(vec![lowest_card]));
+        assert!(!game.check_move(vec![
+            PlayedCard::new(Rank::King, Suit::Hearts, false)
+        ]));
+        assert_eq!(game.suggest_move("b"), Ok(vec![lowest_card]));
+    }
+
+    #[test]
+    fn check_move_agrees_with_the_round_about_jokers_as_the_lowest_card() {
+        let players = vec![
+            Player::new("a".to_string(), vec![
+                Card::Standard{deck_id: 0, rank: Rank::Five, suit: Suit::Clubs},
+            ]),
+            Player::new("b".to_string(), vec![
+                Card::Standard{deck_id: 0, rank: Rank::Four, suit: Suit::Spades},
+                Card::Joker{deck_id: 0},
+            ]),
+        ];
+        let mut game = Game::from_players(
+            1, 1, players, get_suit_array(), DEFAULT_RULESET, None
+        );
+        let joker = PlayedCard::new(Rank::Four, Suit::Spades, true);
+
+        assert!(game.check_move(vec![joker]));
+        assert!(game.play_move("b", vec![joker]).is_ok());
+    }
+
+    #[test]
+    fn check_move_with_ruleset_follows_the_hand_rules() {
+        let two_pair = vec![
+            PlayedCard::new(Rank::Four, Suit::Clubs, false),
+            PlayedCard::new(Rank::Four, Suit::Spades, false),
+            PlayedCard::new(Rank::Six, Suit::Clubs, false),
+            PlayedCard::new(Rank::Six, Suit::Hearts, false),
+        ];
+        let ruleset = Ruleset {
+            hand_rules: HandRules { two_pair: true, ..DEFAULT_HAND_RULES },
+            ..DEFAULT_RULESET
+        };
+        let check = |hand: Vec<PlayedCard>, last_move, ruleset: &Ruleset| {
+            Game::check_move_with_ruleset(
+                hand,
+                last_move,
+                None,
+                get_suit_array(),
+                get_rank_array(),
+                ruleset
+            )
+        };
+
+        assert!(!check(two_pair.clone(), Some(Hand::Pass), &DEFAULT_RULESET));
+        assert!(check(two_pair.clone(), Some(Hand::Pass), &ruleset));
+
+        let last_move = Hand::build_with_rules(vec![
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Three, Suit::Spades, false),
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Hearts, false),
+        ], &ruleset.hand_rules);
+
+        assert!(check(two_pair.clone(), last_move, &ruleset));
+        assert!(!check(vec![], last_move, &ruleset));
+        assert!(!check(
+            vec![PlayedCard::new(Rank::Two, Suit::Spades, false)],
+            last_move,
+            &ruleset
+        ));
+    }
+
+    #[test]
+    fn check_move_with_ruleset_requires_the_given_first_card() {
+        let first_card = PlayedCard::new(Rank::Four, Suit::Spades, false);
+        let check = |hand: Vec<PlayedCard>| Game::check_move_with_ruleset(
+            hand,
+            None,
+            Some(first_card),
+            get_suit_array(),
+            get_rank_array(),
+            &DEFAULT_RULESET
+        );
+
+        assert!(check(vec![first_card]));
+        assert!(check(vec![PlayedCard::new(Rank::Four, Suit::Spades, true)]));
+        assert!(!check(vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)]));
+        assert!(!check(vec![]));
+    }
+
+    #[test]
+    fn check_move_m_uses_the_default_rules() {
+        let three = PlayedCard::new(Rank::Three, Suit::Clubs, false);
+        let four = PlayedCard::new(Rank::Four, Suit::Clubs, false);
+        let check = |hand: Vec<PlayedCard>, last_move| Game::check_move_m(
+            hand,
+            last_move,
+            get_suit_array(),
+            get_rank_array(),
+            FlushPrecedence::Rank
+        );
+
+        assert!(check(vec![three], None));
+        assert!(!check(vec![four], None));
+        assert!(check(vec![four], Some(Hand::Single(three))));
+        assert!(!check(vec![three], Some(Hand::Single(four))));
+        assert!(!check(vec![], Some(Hand::Single(three))));
+        assert!(check(vec![], Some(Hand::Pass)));
+    }
+
     #[test]
     fn check_move_returns_false_when_unable_to_play() {
         let ids = ["a".to_string(), "b".to_string()];
@@ -634,7 +1537,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!["c".to_string()],
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -697,7 +1604,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!["c".to_string()],
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -760,7 +1671,11 @@ mod tests {
             num_jokers: 1,
             round,
             winners: vec!["c".to_string()],
-            ruleset: DEFAULT_RULESET
+            ruleset: DEFAULT_RULESET,
+            seed: None,
+            history: vec!(),
+            transfers: vec!(),
+            strategies: BTreeMap::new(),
         };
 
         let hand = vec![
@@ -776,4 +1691,89 @@ mod tests {
         assert!(!result);
     }
 
+
+    fn random_ruleset(rng: &mut StdRng) -> Ruleset {
+        let starting_rules = [
+            StartingRule::LowestCard,
+            StartingRule::LowestCardNotRequired,
+            StartingRule::PreviousWinner,
+        ];
+        let straight_rules = [
+            StraightRule::NoWrap,
+            StraightRule::LowAce,
+            StraightRule::LowTwo,
+            StraightRule::WrapAround,
+        ];
+
+        Ruleset {
+            reversals_enabled: rng.gen(),
+            flush_precedence: if rng.gen() {
+                FlushPrecedence::Suit
+            } else {
+                FlushPrecedence::Rank
+            },
+            starting_rule: starting_rules[rng.gen_range(0, 3)],
+            hand_rules: HandRules {
+                straight_rule: straight_rules[rng.gen_range(0, 4)],
+                five_of_a_kind: rng.gen(),
+                two_pair: rng.gen(),
+                full_house_duplicates: rng.gen(),
+                straight_flushes: rng.gen(),
+            },
+            bombs: if rng.gen() {
+                Some(BombRule {
+                    four_of_a_kind: rng.gen(),
+                    straight_flush: rng.gen(),
+                    five_of_a_kind: rng.gen(),
+                    beats_up_to: rng.gen_range(1, 5),
+                })
+            } else {
+                None
+            },
+            flush_kickers: rng.gen(),
+            ..Ruleset::default()
+        }
+    }
+
+    #[test]
+    fn suggested_moves_are_always_accepted() {
+        let mut rng = StdRng::seed_from_u64(21);
+
+        for deal in 0..20 {
+            let ruleset = random_ruleset(&mut rng);
+            let num_players = rng.gen_range(2, 5);
+            let ids: Vec<String> = (0..num_players)
+                .map(|i| i.to_string())
+                .collect();
+            let mut game = Game::new_with_seed(
+                rng.gen_range(1, 3),
+                rng.gen_range(0, 3),
+                &ids,
+                get_suit_array(),
+                ruleset,
+                rng.gen(),
+            );
+
+            for _ in 0..1000 {
+                let id = match game.get_next_player() {
+                    Some(id) => id,
+                    None => break,
+                };
+                let cards = game.suggest_move(&id).unwrap();
+
+                assert_eq!(
+                    game.play_move(&id, cards.clone()),
+                    Ok(()),
+                    "deal {} under {:?}: {} played {:?} over {:?}",
+                    deal,
+                    ruleset,
+                    id,
+                    cards,
+                    game.get_last_move()
+                );
+            }
+
+            assert!(game.get_next_player().is_none());
+        }
+    }
 }
@@ -1,6 +1,9 @@
-use crate::cards::{PlayedCard, Rank};
+use super::{HandRules, StraightRule, DEFAULT_HAND_RULES};
+use crate::cards::{ParseError, PlayedCard, Rank};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 #[macro_export]
 macro_rules! build_fct {
@@ -12,6 +15,16 @@ macro_rules! build_fct {
     };
 }
 
+/// Parses a hand from card notation, giving the parse error back
+/// when the cards don't make a hand
+#[macro_export]
+macro_rules! hand {
+    ($($cards:expr),*) => {{
+        let cards: &[&str] = &[$($cards),*];
+        cards.join(" ").parse::<$crate::game::Hand>()
+    }};
+}
+
 #[derive(Clone, Debug, PartialEq, Copy, Serialize, Deserialize)]
 /// Type of hand that can be played
 #[serde(tag = "type", content = "cards")]
@@ -25,19 +38,86 @@ pub enum Hand {
     Pair(PlayedCard, PlayedCard),
     /// 3 of a kind
     Prial(PlayedCard, PlayedCard, PlayedCard),
+    /// 2 pairs of different ranks, when the rules allow it
+    TwoPair(PlayedCard, PlayedCard, PlayedCard, PlayedCard),
     /// 5 card trick
     FiveCardTrick(Trick),
 }
 
+/// Shape of a hand, without the cards
+#[derive(Clone, Debug, PartialEq, Copy, Serialize, Deserialize)]
+pub enum HandType {
+    Pass,
+    Single,
+    Pair,
+    Prial,
+    TwoPair,
+    FiveCardTrick(TrickType),
+}
+
+/// Why a set of cards couldn't be built into a hand
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InvalidHandReason {
+    /// hands must have 0, 1, 2, 3 or 5 cards, or 4 for a two pair
+    UnsupportedCardCount(usize),
+    /// pairs and prials must be a single rank, and two pairs
+    /// two ranks
+    MismatchedRanks(Vec<Rank>),
+    /// 5 cards that don't make any trick
+    NotATrick,
+    /// a hand the rules don't allow
+    NotAllowed(HandType),
+    /// a full house holding the same card twice, when the rules
+    /// don't allow it
+    DuplicateCards,
+}
+
 impl Hand {
     pub fn build(cards: Vec<PlayedCard>) -> Option<Hand> {
+        Self::try_build(cards).ok()
+    }
+
+    pub fn try_build(
+        cards: Vec<PlayedCard>
+    ) -> Result<Hand, InvalidHandReason> {
+        Self::try_build_with_rules(cards, &DEFAULT_HAND_RULES)
+    }
+
+    pub fn build_with_rules(
+        cards: Vec<PlayedCard>,
+        hand_rules: &HandRules
+    ) -> Option<Hand> {
+        Self::try_build_with_rules(cards, hand_rules).ok()
+    }
+
+    /// Build a hand from the cards, if `hand_rules` allow it
+    pub fn try_build_with_rules(
+        cards: Vec<PlayedCard>,
+        hand_rules: &HandRules
+    ) -> Result<Hand, InvalidHandReason> {
         match cards.len() {
-            0 => Some(Hand::Pass),
-            1 => Some(Hand::Single(cards[0])),
+            0 => Ok(Hand::Pass),
+            1 => Ok(Hand::Single(cards[0])),
             2 => Self::check_valid_pair(cards),
             3 => Self::check_valid_prial(cards),
-            5 => Self::check_valid_fct(cards),
-            _ => None,
+            4 if hand_rules.two_pair => Self::check_valid_two_pair(cards),
+            5 => Self::check_valid_fct(cards, hand_rules.straight_rule)
+                .ok_or(InvalidHandReason::NotATrick)
+                .and_then(|hand| Self::check_allowed_trick(hand, hand_rules)),
+            n => Err(InvalidHandReason::UnsupportedCardCount(n)),
+        }
+    }
+
+    pub fn get_type(&self) -> HandType {
+        match *self {
+            Hand::Pass => HandType::Pass,
+            Hand::Single(_) => HandType::Single,
+            Hand::Pair(_, _) => HandType::Pair,
+            Hand::Prial(_, _, _) => HandType::Prial,
+            Hand::TwoPair(_, _, _, _) => HandType::TwoPair,
+            Hand::FiveCardTrick(trick) => {
+                HandType::FiveCardTrick(trick.trick_type)
+            },
         }
     }
 
@@ -47,27 +127,61 @@ impl Hand {
             Hand::Single(c) => vec![c],
             Hand::Pair(c, d) => vec![c, d],
             Hand::Prial(c, d, e) => vec![c, d, e],
+            Hand::TwoPair(c, d, e, f) => vec![c, d, e, f],
             Hand::FiveCardTrick(trick) => trick.cards.to_vec(),
         }
     }
 
-    fn check_valid_pair(cards: Vec<PlayedCard>) -> Option<Hand> {
-        if Self::get_counts(cards.clone()).len() == 1 {
-            Some(Hand::Pair(cards[0], cards[1]))
-        } else {
-            None
+    fn check_valid_pair(
+        cards: Vec<PlayedCard>
+    ) -> Result<Hand, InvalidHandReason> {
+        Self::check_same_rank(&cards)?;
+        Ok(Hand::Pair(cards[0], cards[1]))
+    }
+
+    fn check_valid_prial(
+        cards: Vec<PlayedCard>
+    ) -> Result<Hand, InvalidHandReason> {
+        Self::check_same_rank(&cards)?;
+        Ok(Hand::Prial(cards[0], cards[1], cards[2]))
+    }
+
+    fn check_valid_two_pair(
+        c: Vec<PlayedCard>
+    ) -> Result<Hand, InvalidHandReason> {
+        let cards = Self::sort_cards(c);
+        let rank_count = Self::get_counts(cards.clone());
+
+        if rank_count.len() == 2 && rank_count.values().all(|&n| n == 2) {
+            return Ok(Hand::TwoPair(cards[0], cards[1], cards[2], cards[3]));
         }
+
+        let mut ranks: Vec<Rank> = cards.iter().map(|c| c.get_rank()).collect();
+        ranks.dedup();
+        Err(InvalidHandReason::MismatchedRanks(ranks))
     }
 
-    fn check_valid_prial(cards: Vec<PlayedCard>) -> Option<Hand> {
-        if Self::get_counts(cards.clone()).len() == 1 {
-            Some(Hand::Prial(cards[0], cards[1], cards[2]))
+    fn check_same_rank(
+        cards: &[PlayedCard]
+    ) -> Result<(), InvalidHandReason> {
+        let mut ranks: Vec<Rank> = vec![];
+        for card in cards.iter() {
+            if !ranks.contains(&card.get_rank()) {
+                ranks.push(card.get_rank());
+            }
+        }
+
+        if ranks.len() == 1 {
+            Ok(())
         } else {
-            None
+            Err(InvalidHandReason::MismatchedRanks(ranks))
         }
     }
 
-    fn check_valid_fct(c: Vec<PlayedCard>) -> Option<Hand> {
+    fn check_valid_fct(
+        c: Vec<PlayedCard>,
+        straight_rule: StraightRule
+    ) -> Option<Hand> {
         let cards = Self::sort_cards(c);
         let rank_count = Self::get_counts(cards.clone());
         match rank_count.len() {
@@ -79,7 +193,7 @@ impl Hand {
             },
             _ => {
                 let fct_type = (
-                    Self::is_straight(cards.clone()),
+                    Self::is_straight(&cards, straight_rule),
                     Self::is_flush(cards.clone()),
                 );
                 match fct_type {
@@ -92,11 +206,47 @@ impl Hand {
         }
     }
 
-    fn is_straight(c: Vec<PlayedCard>) -> bool {
-        c.iter().enumerate().all(|(i, &card)| {
-            i == 0
-                || Self::get_previous_rank(card).is_some()
-                    && c[i - 1].get_rank() == Self::get_previous_rank(card).unwrap()
+    fn check_allowed_trick(
+        hand: Hand,
+        hand_rules: &HandRules
+    ) -> Result<Hand, InvalidHandReason> {
+        let trick = match hand {
+            Hand::FiveCardTrick(trick) => trick,
+            _ => return Ok(hand),
+        };
+
+        match trick.trick_type {
+            TrickType::FiveOfAKind if !hand_rules.five_of_a_kind => {
+                Err(InvalidHandReason::NotAllowed(hand.get_type()))
+            },
+            TrickType::FullHouse if !hand_rules.full_house_duplicates => {
+                let cards = trick.cards;
+                let has_duplicates = cards.iter().enumerate().any(|(i, a)| {
+                    cards[i + 1..].iter().any(|b| {
+                        a.get_rank() == b.get_rank()
+                            && a.get_suit() == b.get_suit()
+                    })
+                });
+
+                if has_duplicates {
+                    Err(InvalidHandReason::DuplicateCards)
+                } else {
+                    Ok(hand)
+                }
+            },
+            TrickType::StraightFlush if !hand_rules.straight_flushes => {
+                Ok(Hand::FiveCardTrick(Trick {
+                    trick_type: TrickType::Flush,
+                    cards: trick.cards,
+                }))
+            },
+            _ => Ok(hand),
+        }
+    }
+
+    fn is_straight(c: &[PlayedCard], straight_rule: StraightRule) -> bool {
+        straight_rule.get_runs().iter().any(|run| {
+            run.iter().all(|&rank| c.iter().any(|card| card.get_rank() == rank))
         })
     }
 
@@ -116,23 +266,43 @@ impl Hand {
         c.sort();
         c
     }
+}
+
+/// `[3C 3D]` for a pair, `[]` for a pass
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, card) in self.to_cards().iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            fmt::Display::fmt(card, f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// brackets are optional - `"3C 3D"` and `"[3C 3D]"` are both
+/// accepted, as is `"pass"`
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Hand, ParseError> {
+        let trimmed = s.trim();
+        let inner = trimmed.strip_prefix('[')
+            .and_then(|t| t.strip_suffix(']'))
+            .unwrap_or(trimmed);
 
-    fn get_previous_rank(card: PlayedCard) -> Option<Rank> {
-        match card.get_rank() {
-            Rank::Three => None,
-            Rank::Four => Some(Rank::Three),
-            Rank::Five => Some(Rank::Four),
-            Rank::Six => Some(Rank::Five),
-            Rank::Seven => Some(Rank::Six),
-            Rank::Eight => Some(Rank::Seven),
-            Rank::Nine => Some(Rank::Eight),
-            Rank::Ten => Some(Rank::Nine),
-            Rank::Jack => Some(Rank::Ten),
-            Rank::Queen => Some(Rank::Jack),
-            Rank::King => Some(Rank::Queen),
-            Rank::Ace => Some(Rank::King),
-            Rank::Two => Some(Rank::Ace),
+        if inner.trim() == "pass" {
+            return Ok(Hand::Pass);
         }
+
+        let cards = inner.split_whitespace()
+            .map(|token| token.parse())
+            .collect::<Result<Vec<PlayedCard>, ParseError>>()?;
+
+        Hand::build(cards)
+            .ok_or_else(|| ParseError::InvalidHand(s.to_string()))
     }
 }
 
@@ -207,6 +377,43 @@ mod tests {
         assert_eq!(hand, None);
     }
 
+    #[test]
+    fn invalid_hands_report_why_they_were_rejected() {
+        let three_of_clubs = PlayedCard::new(Rank::Three, Suit::Clubs, false);
+        let four_of_hearts = PlayedCard::new(Rank::Four, Suit::Hearts, false);
+
+        assert_eq!(
+            Hand::try_build(vec![three_of_clubs, four_of_hearts]),
+            Err(InvalidHandReason::MismatchedRanks(
+                vec![Rank::Three, Rank::Four]
+            ))
+        );
+        assert_eq!(
+            Hand::try_build(vec![three_of_clubs; 4]),
+            Err(InvalidHandReason::UnsupportedCardCount(4))
+        );
+        assert_eq!(
+            Hand::try_build(vec![
+                three_of_clubs,
+                four_of_hearts,
+                PlayedCard::new(Rank::Six, Suit::Diamonds, false),
+                PlayedCard::new(Rank::Eight, Suit::Spades, false),
+                PlayedCard::new(Rank::Ten, Suit::Clubs, false),
+            ]),
+            Err(InvalidHandReason::NotATrick)
+        );
+    }
+
+    #[test]
+    fn hands_have_a_type() {
+        assert_eq!(hand!().unwrap().get_type(), HandType::Pass);
+        assert_eq!(hand!("3C", "3D").unwrap().get_type(), HandType::Pair);
+        assert_eq!(
+            hand!("3C", "4C", "5D", "6S", "7H").unwrap().get_type(),
+            HandType::FiveCardTrick(TrickType::Straight)
+        );
+    }
+
     #[test]
     fn three_cards_of_same_rank_is_a_prial() {
         let played_three_of_clubs = PlayedCard::new(Rank::Three, Suit::Clubs, false);
@@ -416,4 +623,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hands_can_be_displayed_and_parsed() {
+        let pair = Hand::Pair(
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Three, Suit::Diamonds, true),
+        );
+
+        assert_eq!(pair.to_string(), "[3C *3D]");
+        assert_eq!(format!("{:#}", pair), "[3♣ *3♦]");
+        assert_eq!("[3C *3D]".parse::<Hand>(), Ok(pair));
+        assert_eq!("3C *3D".parse::<Hand>(), Ok(pair));
+        assert_eq!(Hand::Pass.to_string(), "[]");
+        assert_eq!("[]".parse::<Hand>(), Ok(Hand::Pass));
+        assert_eq!("pass".parse::<Hand>(), Ok(Hand::Pass));
+        assert_eq!(
+            "[3C 4D]".parse::<Hand>(),
+            Err(ParseError::InvalidHand("[3C 4D]".to_string()))
+        );
+    }
+
+    #[test]
+    fn hand_macro_builds_hands_from_notation() {
+        let cards = [
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Four, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Six, Suit::Hearts, false),
+            PlayedCard::new(Rank::Seven, Suit::Hearts, false),
+        ];
+
+        assert_eq!(hand!("3C 4C 5C 6H 7H").ok(), build_fct!(Straight, cards));
+        assert_eq!(
+            hand!("3C", "4C", "5C", "6H", "7H").ok(),
+            build_fct!(Straight, cards)
+        );
+        assert_eq!(hand!(), Ok(Hand::Pass));
+        assert_eq!(
+            hand!("3C 4C"),
+            Err(ParseError::InvalidHand("3C 4C".to_string()))
+        );
+    }
+
+    fn straights(straight_rule: StraightRule) -> HandRules {
+        HandRules { straight_rule, ..DEFAULT_HAND_RULES }
+    }
+
+    fn cards(cards: &[&str]) -> Vec<PlayedCard> {
+        cards.iter().map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn wrapped_straights_depend_on_the_straight_rule() {
+        let wheel = cards(&["AS", "2C", "3H", "4D", "5C"]);
+        let wrapped = cards(&["QS", "KC", "AH", "2D", "3C"]);
+
+        assert_eq!(
+            Hand::try_build(wheel.clone()),
+            Err(InvalidHandReason::NotATrick)
+        );
+        assert!(
+            Hand::build_with_rules(wheel, &straights(StraightRule::LowAce))
+                .is_some()
+        );
+        assert!(
+            Hand::build_with_rules(
+                wrapped.clone(),
+                &straights(StraightRule::LowTwo)
+            ).is_none()
+        );
+        assert_eq!(
+            Hand::build_with_rules(wrapped, &straights(StraightRule::WrapAround))
+                .map(|hand| hand.get_type()),
+            Some(HandType::FiveCardTrick(TrickType::Straight))
+        );
+    }
+
+    #[test]
+    fn two_pair_is_only_a_hand_when_the_rules_allow_it() {
+        let two_pair = cards(&["3C", "3D", "9H", "9S"]);
+        let allowed = HandRules { two_pair: true, ..DEFAULT_HAND_RULES };
+
+        assert_eq!(
+            Hand::try_build(two_pair.clone()),
+            Err(InvalidHandReason::UnsupportedCardCount(4))
+        );
+        assert_eq!(
+            Hand::build_with_rules(two_pair, &allowed)
+                .map(|hand| hand.get_type()),
+            Some(HandType::TwoPair)
+        );
+        assert_eq!(
+            Hand::try_build_with_rules(
+                cards(&["3C", "3D", "3H", "9S"]),
+                &allowed
+            ),
+            Err(InvalidHandReason::MismatchedRanks(vec![Rank::Three, Rank::Nine]))
+        );
+    }
+
+    #[test]
+    fn five_card_tricks_can_be_ruled_out() {
+        let rules = HandRules {
+            five_of_a_kind: false,
+            full_house_duplicates: false,
+            straight_flushes: false,
+            ..DEFAULT_HAND_RULES
+        };
+
+        assert_eq!(
+            Hand::try_build_with_rules(
+                cards(&["7C", "7D", "7H", "7S", "7S"]),
+                &rules
+            ),
+            Err(InvalidHandReason::NotAllowed(
+                HandType::FiveCardTrick(TrickType::FiveOfAKind)
+            ))
+        );
+        assert_eq!(
+            Hand::try_build_with_rules(
+                cards(&["7C", "7C", "7H", "9S", "9D"]),
+                &rules
+            ),
+            Err(InvalidHandReason::DuplicateCards)
+        );
+        assert!(
+            Hand::build_with_rules(cards(&["7C", "7D", "7H", "9S", "9S"]), &rules)
+                .is_none()
+        );
+        assert_eq!(
+            Hand::build_with_rules(
+                cards(&["3C", "4C", "5C", "6C", "7C"]),
+                &rules
+            ).map(|hand| hand.get_type()),
+            Some(HandType::FiveCardTrick(TrickType::Flush))
+        );
+    }
 }
@@ -0,0 +1,18 @@
+use super::Hand;
+use serde::{Deserialize, Serialize};
+
+/// A single entry in a game's move history
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    /// turn number, starting from 1
+    pub turn: usize,
+    pub player_id: String,
+    /// the hand submitted - `Hand::Pass` for a pass
+    pub hand: Hand,
+    /// the table was cleared after this move
+    pub cleared_table: bool,
+    /// suit and rank orders were reversed by this move
+    pub reversed: bool,
+    /// the player played their last card with this move
+    pub went_out: bool,
+}
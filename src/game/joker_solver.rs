@@ -0,0 +1,235 @@
+use super::{
+    compare_hands_with_ruleset,
+    get_candidate_moves,
+    Hand,
+    HandOrdering,
+    HandRules,
+    Ruleset,
+};
+use crate::cards::{Card, PlayedCard, Rank, Suit};
+
+/// Every valid hand that can be made using all of `cards`, with each
+/// joker standing in for any card
+pub fn get_possible_hands(
+    cards: &[Card],
+    hand_rules: &HandRules
+) -> Vec<Hand> {
+    let has_jokers = cards.iter().any(|c| c.get_rank().is_none());
+
+    if !has_jokers {
+        let played: Vec<PlayedCard> = cards.iter()
+            .filter_map(|&c| match c {
+                Card::Standard { rank, suit, .. } => {
+                    Some(PlayedCard::new(rank, suit, false))
+                },
+                Card::Joker { .. } => None,
+            })
+            .collect();
+
+        return Hand::build_with_rules(played, hand_rules)
+            .into_iter()
+            .collect();
+    }
+
+    get_candidate_moves(cards, hand_rules).into_iter()
+        .filter(|played| played.len() == cards.len())
+        .filter_map(|played| {
+            Hand::build_with_rules(played, hand_rules)
+        })
+        .collect()
+}
+
+/// The strongest hand that can be made using all of `cards`
+pub fn get_strongest_hand(
+    cards: &[Card],
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Hand> {
+    let ordering = HandOrdering::from_ruleset(ruleset, suit_order, rank_order);
+
+    get_possible_hands(cards, &ruleset.hand_rules).into_iter()
+        .max_by_key(|&hand| ordering.get_strength_key(hand))
+}
+
+/// The weakest hand that can be made using all of `cards` that
+/// still beats `last_move`
+pub fn get_weakest_beating_hand(
+    cards: &[Card],
+    last_move: Option<Hand>,
+    suit_order: [Suit; 4],
+    rank_order: [Rank; 13],
+    ruleset: &Ruleset,
+) -> Option<Hand> {
+    let ordering = HandOrdering::from_ruleset(ruleset, suit_order, rank_order);
+
+    get_possible_hands(cards, &ruleset.hand_rules).into_iter()
+        .filter(|&hand| match last_move {
+            None | Some(Hand::Pass) => true,
+            Some(last) => compare_hands_with_ruleset(
+                last, hand, ruleset, suit_order, rank_order
+            ),
+        })
+        .min_by_key(|&hand| ordering.get_strength_key(hand))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{
+        HandType,
+        StraightRule,
+        Trick,
+        TrickType,
+        DEFAULT_HAND_RULES,
+        DEFAULT_RULESET,
+    };
+
+    static DEFAULT_RANK_ORDER: [Rank; 13] = [
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+        Rank::Two,
+    ];
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::Standard { deck_id: 0, rank, suit }
+    }
+
+    #[test]
+    fn cards_without_jokers_make_at_most_one_hand() {
+        let pair = vec![
+            card(Rank::Six, Suit::Clubs),
+            card(Rank::Six, Suit::Hearts),
+        ];
+        let mismatched = vec![
+            card(Rank::Six, Suit::Clubs),
+            card(Rank::Seven, Suit::Hearts),
+        ];
+
+        assert_eq!(get_possible_hands(&pair, &DEFAULT_HAND_RULES).len(), 1);
+        assert!(get_possible_hands(&mismatched, &DEFAULT_HAND_RULES).is_empty());
+    }
+
+    #[test]
+    fn a_joker_can_complete_a_pair() {
+        let cards = vec![
+            card(Rank::Six, Suit::Clubs),
+            Card::Joker { deck_id: 0 },
+        ];
+
+        let hands = get_possible_hands(&cards, &DEFAULT_HAND_RULES);
+
+        assert_eq!(hands.len(), 4);
+        assert_eq!(
+            get_strongest_hand(
+                &cards,
+                get_suit_array(),
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
+            ),
+            Some(Hand::Pair(
+                PlayedCard::new(Rank::Six, Suit::Clubs, false),
+                PlayedCard::new(Rank::Six, Suit::Spades, true),
+            ))
+        );
+    }
+
+    #[test]
+    fn it_finds_the_weakest_hand_that_beats_the_last_move() {
+        let cards = vec![
+            card(Rank::Six, Suit::Clubs),
+            Card::Joker { deck_id: 0 },
+        ];
+        let last_move = Hand::Pair(
+            PlayedCard::new(Rank::Six, Suit::Hearts, false),
+            PlayedCard::new(Rank::Six, Suit::Clubs, false),
+        );
+
+        assert_eq!(
+            get_weakest_beating_hand(
+                &cards,
+                Some(last_move),
+                get_suit_array(),
+                DEFAULT_RANK_ORDER,
+                &DEFAULT_RULESET
+            ),
+            Some(Hand::Pair(
+                PlayedCard::new(Rank::Six, Suit::Clubs, false),
+                PlayedCard::new(Rank::Six, Suit::Diamonds, true),
+            ))
+        );
+    }
+
+    #[test]
+    fn a_joker_can_complete_a_straight_flush() {
+        let cards = vec![
+            card(Rank::Three, Suit::Hearts),
+            card(Rank::Four, Suit::Hearts),
+            card(Rank::Five, Suit::Hearts),
+            card(Rank::Six, Suit::Hearts),
+            Card::Joker { deck_id: 0 },
+        ];
+
+        let strongest = get_strongest_hand(
+            &cards,
+            get_suit_array(),
+            DEFAULT_RANK_ORDER,
+            &DEFAULT_RULESET
+        ).unwrap();
+
+        assert_eq!(
+            strongest,
+            Hand::FiveCardTrick(Trick {
+                trick_type: TrickType::StraightFlush,
+                cards: [
+                    PlayedCard::new(Rank::Three, Suit::Hearts, false),
+                    PlayedCard::new(Rank::Four, Suit::Hearts, false),
+                    PlayedCard::new(Rank::Five, Suit::Hearts, false),
+                    PlayedCard::new(Rank::Six, Suit::Hearts, false),
+                    PlayedCard::new(Rank::Seven, Suit::Hearts, true),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn a_joker_can_make_a_low_straight_when_the_rules_allow_it() {
+        let cards = vec![
+            card(Rank::Three, Suit::Hearts),
+            card(Rank::Four, Suit::Hearts),
+            card(Rank::Five, Suit::Hearts),
+            card(Rank::Six, Suit::Hearts),
+            Card::Joker { deck_id: 0 },
+        ];
+
+        let weakest = get_weakest_beating_hand(
+            &cards,
+            None,
+            get_suit_array(),
+            DEFAULT_RANK_ORDER,
+            &Ruleset {
+                hand_rules: HandRules {
+                    straight_rule: StraightRule::LowTwo,
+                    ..DEFAULT_HAND_RULES
+                },
+                ..DEFAULT_RULESET
+            }
+        ).unwrap();
+
+        assert!(weakest.to_cards().contains(
+            &PlayedCard::new(Rank::Two, Suit::Clubs, true)
+        ));
+        assert_eq!(weakest.get_type(), HandType::FiveCardTrick(TrickType::Straight));
+    }
+}
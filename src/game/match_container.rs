@@ -0,0 +1,698 @@
+use super::{
+    are_highest_cards,
+    get_exchanges,
+    Exchange,
+    ExchangeError,
+    Game,
+    Ruleset,
+    StartingRule,
+    SubmitError,
+};
+use crate::ai::choose_cards_to_give;
+use crate::cards::{Card, PlayedCard, Rank, Suit};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum MatchError {
+    DealInProgress,
+    MatchOver,
+    /// cards are still to be exchanged before the deal can start
+    CardExchangePending,
+    /// the move was rejected by the game
+    Submit(SubmitError),
+}
+
+impl From<SubmitError> for MatchError {
+    fn from(error: SubmitError) -> MatchError {
+        MatchError::Submit(error)
+    }
+}
+
+/// How points are given out at the end of each deal
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScoringScheme {
+    /// points for each finishing place, first place first - the
+    /// player with the most points wins
+    FinishingPlaces(Vec<u32>),
+    /// a penalty point for each card left in hand, multiplied when
+    /// 10 or more cards are left and again for each unplayed 2 -
+    /// the player with the fewest points wins
+    CardPenalty {
+        ten_or_more_multiplier: u32,
+        unplayed_two_multiplier: u32,
+    },
+}
+
+impl ScoringScheme {
+    /// Points for each player in a finished game, in seating order
+    pub fn score_deal(&self, game: &Game) -> Vec<u32> {
+        let finishing_order = get_finishing_order(game);
+
+        game.get_round().get_players().iter()
+            .map(|player| match self {
+                ScoringScheme::FinishingPlaces(points) => {
+                    finishing_order.iter()
+                        .position(|id| id == player.get_id())
+                        .and_then(|place| points.get(place))
+                        .cloned()
+                        .unwrap_or(0)
+                },
+                ScoringScheme::CardPenalty {
+                    ten_or_more_multiplier,
+                    unplayed_two_multiplier,
+                } => {
+                    let hand = player.get_hand();
+                    let twos = hand.iter()
+                        .filter(|c| c.get_rank() == Some(Rank::Two))
+                        .count() as u32;
+                    let mut penalty = hand.len() as u32;
+
+                    // the multipliers are unbounded, so large ones
+                    // cap the penalty rather than overflowing
+                    if hand.len() >= 10 {
+                        penalty = penalty.saturating_mul(
+                            *ten_or_more_multiplier
+                        );
+                    }
+
+                    penalty.saturating_mul(
+                        unplayed_two_multiplier.saturating_pow(twos)
+                    )
+                },
+            })
+            .collect()
+    }
+
+    pub fn lowest_score_wins(&self) -> bool {
+        match self {
+            ScoringScheme::FinishingPlaces(_) => false,
+            ScoringScheme::CardPenalty { .. } => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MatchEnd {
+    /// the match ends after the deal where any player reaches
+    /// this many points
+    TargetScore(u32),
+    /// the match ends after this many deals
+    Deals(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchRules {
+    pub scoring: ScoringScheme,
+    pub end: MatchEnd,
+}
+
+/// The outcome of a single deal in a match
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DealResult {
+    pub seed: u64,
+    pub finishing_order: Vec<String>,
+    /// points scored by each player, in seating order
+    pub points: Vec<u32>,
+}
+
+/// A series of games between the same players, with
+/// scores carried across deals
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Match {
+    player_ids: Vec<String>,
+    num_decks: u8,
+    num_jokers: u8,
+    suit_order: [Suit; 4],
+    ruleset: Ruleset,
+    rules: MatchRules,
+    seed: u64,
+    game: Game,
+    results: Vec<DealResult>,
+    scores: Vec<u32>,
+    #[serde(default)]
+    exchanges: Vec<Exchange>,
+}
+
+impl Match {
+    pub fn new(
+        num_decks: u8,
+        num_jokers: u8,
+        player_ids: &[String],
+        suit_order: [Suit; 4],
+        ruleset: Ruleset,
+        rules: MatchRules,
+    ) -> Match {
+        Self::new_with_seed(
+            num_decks,
+            num_jokers,
+            player_ids,
+            suit_order,
+            ruleset,
+            rules,
+            rand::random()
+        )
+    }
+
+    /// Deal `n` of the match (counting from 0) is dealt from `seed + n`
+    pub fn new_with_seed(
+        num_decks: u8,
+        num_jokers: u8,
+        player_ids: &[String],
+        suit_order: [Suit; 4],
+        ruleset: Ruleset,
+        rules: MatchRules,
+        seed: u64,
+    ) -> Match {
+        let game = Game::new_with_seed(
+            num_decks,
+            num_jokers,
+            player_ids,
+            suit_order,
+            ruleset,
+            seed
+        );
+
+        let mut new_match = Match {
+            player_ids: player_ids.to_vec(),
+            num_decks,
+            num_jokers,
+            suit_order,
+            ruleset,
+            rules,
+            seed,
+            game,
+            results: vec!(),
+            scores: vec![0; player_ids.len()],
+            exchanges: vec!(),
+        };
+        new_match.choose_starting_player(0);
+
+        new_match
+    }
+
+    pub fn get_game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn play_move(
+        &mut self,
+        player_id: &str,
+        player_move: Vec<PlayedCard>,
+    ) -> Result<(), MatchError> {
+        if !self.exchanges.is_empty() {
+            return Err(MatchError::CardExchangePending);
+        }
+
+        self.game.play_move(player_id, player_move)?;
+
+        if self.is_deal_over() {
+            self.score_deal();
+        }
+
+        Ok(())
+    }
+
+    /// Start the next deal once the current one has finished. Any
+    /// card exchanges from the ruleset have to be made before play
+    pub fn next_deal(&mut self) -> Result<&Game, MatchError> {
+        if !self.is_deal_over() {
+            return Err(MatchError::DealInProgress);
+        }
+
+        if self.is_over() {
+            return Err(MatchError::MatchOver);
+        }
+
+        self.game = Game::new_with_seed(
+            self.num_decks,
+            self.num_jokers,
+            &self.player_ids,
+            self.suit_order,
+            self.ruleset,
+            self.get_deal_seed(self.results.len())
+        );
+        self.choose_starting_player(self.results.len());
+        self.exchanges = self.results.last()
+            .map(|result| get_exchanges(
+                &result.finishing_order,
+                self.ruleset.card_exchange
+            ))
+            .unwrap_or_default();
+
+        Ok(&self.game)
+    }
+
+    /// Exchanges still to be made before the deal can be played
+    pub fn get_pending_exchanges(&self) -> Vec<Exchange> {
+        self.exchanges.clone()
+    }
+
+    /// Make the exchange due from a player. Once a loser has given
+    /// their cards, the winner owes the same number back
+    pub fn exchange_cards(
+        &mut self,
+        from: &str,
+        cards: Vec<Card>
+    ) -> Result<(), ExchangeError> {
+        let index = self.exchanges.iter()
+            .position(|e| e.from == from)
+            .ok_or(ExchangeError::NoExchangeDue)?;
+        let exchange = self.exchanges[index].clone();
+
+        if cards.len() != exchange.count {
+            return Err(ExchangeError::WrongNumberOfCards {
+                expected: exchange.count,
+                given: cards.len(),
+            });
+        }
+
+        let mut giver = self.game.get_player(from)
+            .ok_or(ExchangeError::UnknownPlayer)?;
+        let hand = giver.get_hand();
+        giver.remove_cards(&cards)
+            .map_err(ExchangeError::PlayerDoesntHaveCard)?;
+
+        if exchange.must_give_highest && !are_highest_cards(
+            &hand,
+            &cards,
+            self.game.get_suit_order(),
+            self.game.get_rank_order()
+        ) {
+            return Err(ExchangeError::MustGiveHighestCards);
+        }
+
+        self.game.transfer_cards(from, &exchange.to, &cards)?;
+        self.exchanges.remove(index);
+
+        if exchange.must_give_highest {
+            self.exchanges.push(Exchange {
+                from: exchange.to,
+                to: exchange.from,
+                count: exchange.count,
+                must_give_highest: false,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The cards the cpu would give for the exchange due from a player
+    pub fn suggest_exchange(&self, from: &str) -> Result<Vec<Card>, ExchangeError> {
+        let exchange = self.exchanges.iter()
+            .find(|e| e.from == from)
+            .ok_or(ExchangeError::NoExchangeDue)?;
+        let player = self.game.get_player(from)
+            .ok_or(ExchangeError::UnknownPlayer)?;
+
+        Ok(choose_cards_to_give(
+            &player.get_hand(),
+            exchange.count,
+            exchange.must_give_highest,
+            self.game.get_suit_order(),
+            self.game.get_rank_order()
+        ))
+    }
+
+    pub fn is_deal_over(&self) -> bool {
+        self.game.get_next_player().is_none()
+    }
+
+    pub fn is_over(&self) -> bool {
+        if !self.is_deal_over() {
+            return false;
+        }
+
+        match self.rules.end {
+            MatchEnd::TargetScore(target) => {
+                self.scores.iter().any(|&score| score >= target)
+            },
+            MatchEnd::Deals(deals) => self.results.len() >= deals,
+        }
+    }
+
+    /// The player with the best score, once the match is over
+    pub fn get_winner(&self) -> Option<String> {
+        if !self.is_over() {
+            return None;
+        }
+
+        self.get_standings().first().map(|(id, _)| id.to_string())
+    }
+
+    /// Players and their scores, in seating order
+    pub fn get_scores(&self) -> Vec<(String, u32)> {
+        self.player_ids.iter()
+            .cloned()
+            .zip(self.scores.iter().cloned())
+            .collect()
+    }
+
+    /// Players and their scores, best first
+    pub fn get_standings(&self) -> Vec<(String, u32)> {
+        let mut standings = self.get_scores();
+        if self.rules.scoring.lowest_score_wins() {
+            standings.sort_by_key(|&(_, score)| score);
+        } else {
+            standings.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        }
+
+        standings
+    }
+
+    pub fn get_results(&self) -> Vec<DealResult> {
+        self.results.clone()
+    }
+
+    /// The number of the current deal, starting from 1
+    pub fn get_deal_number(&self) -> usize {
+        if self.is_deal_over() {
+            self.results.len()
+        } else {
+            self.results.len() + 1
+        }
+    }
+
+    /// The dealer of the current deal - the deal passes to the
+    /// next player in seating order each time
+    pub fn get_dealer(&self) -> String {
+        let deal = self.get_deal_number().saturating_sub(1);
+
+        self.player_ids[deal % self.player_ids.len()].to_string()
+    }
+
+    pub fn get_rules(&self) -> MatchRules {
+        self.rules.clone()
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn get_deal_seed(&self, deal: usize) -> u64 {
+        self.seed.wrapping_add(deal as u64)
+    }
+
+    fn choose_starting_player(&mut self, deal: usize) {
+        let last_result = self.results.last();
+        let starting_player = match self.ruleset.starting_rule {
+            StartingRule::PreviousWinner => last_result
+                .and_then(|r| r.finishing_order.first().cloned()),
+            StartingRule::PreviousLoser => last_result
+                .and_then(|r| r.finishing_order.last().cloned()),
+            StartingRule::DealersLeft => Some(
+                self.player_ids[(deal + 1) % self.player_ids.len()].to_string()
+            ),
+            StartingRule::LowestCard
+                | StartingRule::LowestCardNotRequired => None,
+        };
+
+        if let Some(player_id) = starting_player {
+            self.game.set_starting_player(&player_id)
+                .expect("starting player is one of the players");
+        }
+    }
+
+    fn score_deal(&mut self) {
+        let points = self.rules.scoring.score_deal(&self.game);
+
+        for (score, deal_points) in self.scores.iter_mut().zip(points.iter()) {
+            *score = score.saturating_add(*deal_points);
+        }
+
+        self.results.push(DealResult {
+            seed: self.get_deal_seed(self.results.len()),
+            finishing_order: get_finishing_order(&self.game),
+            points,
+        });
+    }
+}
+
+/// The winners in the order they went out, followed by anyone
+/// still holding cards
+fn get_finishing_order(game: &Game) -> Vec<String> {
+    let mut order = game.get_winners();
+    let mut remaining: Vec<(usize, String)> = game.get_round()
+        .get_players()
+        .iter()
+        .filter(|p| !order.contains(&p.get_id().to_string()))
+        .map(|p| (p.get_card_count(), p.get_id().to_string()))
+        .collect();
+    remaining.sort_by_key(|(cards, _)| *cards);
+    order.extend(remaining.into_iter().map(|(_, id)| id));
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{
+        Player, StartingRule, DEFAULT_RULESET
+    };
+
+    fn get_finished_game() -> Game {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+        ];
+        let b_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Five,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Two,
+                suit: Suit::Spades,
+            },
+        ];
+        let players = vec![
+            Player::new("a".to_string(), a_cards),
+            Player::new("b".to_string(), b_cards),
+        ];
+        let mut game = Game::from_players(
+            1, 0, players, get_suit_array(), DEFAULT_RULESET, None
+        );
+        game.play_move(
+            "a",
+            vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)]
+        ).unwrap();
+
+        game
+    }
+
+    fn play_deal(match_game: &mut Match) {
+        while let Some(player) = match_game.get_game().get_next_player() {
+            let moves = match_game.get_game().legal_moves(&player).unwrap();
+            let cards = moves.get(1).unwrap_or(&moves[0]).to_vec();
+            match_game.play_move(&player, cards).unwrap();
+        }
+    }
+
+    fn get_ids() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string(), "c".to_string()]
+    }
+
+    #[test]
+    fn finishing_places_score_points() {
+        let scheme = ScoringScheme::FinishingPlaces(vec![3, 1]);
+
+        assert_eq!(scheme.score_deal(&get_finished_game()), vec![3, 1]);
+    }
+
+    #[test]
+    fn remaining_cards_score_penalties() {
+        let scheme = ScoringScheme::CardPenalty {
+            ten_or_more_multiplier: 2,
+            unplayed_two_multiplier: 2,
+        };
+
+        assert_eq!(scheme.score_deal(&get_finished_game()), vec![0, 6]);
+    }
+
+    #[test]
+    fn large_penalty_multipliers_cap_the_score() {
+        let scheme = ScoringScheme::CardPenalty {
+            ten_or_more_multiplier: u32::MAX,
+            unplayed_two_multiplier: 1 << 20,
+        };
+        let game = get_finished_game();
+
+        assert_eq!(scheme.score_deal(&game), vec![0, 3 << 20]);
+
+        let scheme = ScoringScheme::CardPenalty {
+            ten_or_more_multiplier: u32::MAX,
+            unplayed_two_multiplier: u32::MAX,
+        };
+
+        assert_eq!(scheme.score_deal(&game), vec![0, u32::MAX]);
+    }
+
+    #[test]
+    fn scores_carry_across_deals() {
+        let rules = MatchRules {
+            scoring: ScoringScheme::FinishingPlaces(vec![2, 1, 0]),
+            end: MatchEnd::Deals(2),
+        };
+        let mut match_game = Match::new_with_seed(
+            1, 0, &get_ids(), get_suit_array(), DEFAULT_RULESET, rules, 10
+        );
+
+        assert_eq!(match_game.next_deal().err(), Some(MatchError::DealInProgress));
+
+        play_deal(&mut match_game);
+        assert!(match_game.is_deal_over());
+        assert!(!match_game.is_over());
+        assert_eq!(match_game.get_results().len(), 1);
+
+        match_game.next_deal().unwrap();
+        assert_eq!(match_game.get_game().get_seed(), Some(11));
+        assert_eq!(match_game.get_deal_number(), 2);
+
+        play_deal(&mut match_game);
+        assert!(match_game.is_over());
+        assert_eq!(match_game.next_deal().err(), Some(MatchError::MatchOver));
+
+        let total: u32 = match_game.get_scores().iter()
+            .map(|(_, score)| score)
+            .sum();
+        assert_eq!(total, 6);
+        assert_eq!(
+            match_game.get_winner(),
+            Some(match_game.get_standings()[0].0.to_string())
+        );
+    }
+
+    #[test]
+    fn a_match_can_end_on_a_target_score() {
+        let rules = MatchRules {
+            scoring: ScoringScheme::FinishingPlaces(vec![5, 0, 0]),
+            end: MatchEnd::TargetScore(5),
+        };
+        let mut match_game = Match::new_with_seed(
+            1, 0, &get_ids(), get_suit_array(), DEFAULT_RULESET, rules, 3
+        );
+
+        play_deal(&mut match_game);
+
+        assert!(match_game.is_over());
+        assert_eq!(
+            match_game.get_winner(),
+            match_game.get_results()[0].finishing_order.first().cloned()
+        );
+    }
+
+    #[test]
+    fn losers_and_winners_exchange_cards_between_deals() {
+        let ruleset = Ruleset {
+            card_exchange: [2, 0, 0, 0],
+            ..DEFAULT_RULESET
+        };
+        let rules = MatchRules {
+            scoring: ScoringScheme::FinishingPlaces(vec![1, 0, 0]),
+            end: MatchEnd::Deals(3),
+        };
+        let mut match_game = Match::new_with_seed(
+            1, 0, &get_ids(), get_suit_array(), ruleset, rules, 4
+        );
+        play_deal(&mut match_game);
+        let order = match_game.get_results()[0].finishing_order.clone();
+        let winner = order[0].to_string();
+        let loser = order[2].to_string();
+
+        match_game.next_deal().unwrap();
+        let exchanges = match_game.get_pending_exchanges();
+        assert_eq!(exchanges.len(), 1);
+        assert_eq!(exchanges[0].from, loser);
+
+        let next_player = match_game.get_game().get_next_player().unwrap();
+        assert_eq!(
+            match_game.play_move(&next_player, vec![]),
+            Err(MatchError::CardExchangePending)
+        );
+
+        let loser_hand = match_game.get_game().get_player(&loser)
+            .unwrap().get_hand();
+        assert_eq!(
+            match_game.exchange_cards(&loser, loser_hand[..2].to_vec()),
+            Err(ExchangeError::MustGiveHighestCards)
+        );
+
+        let best_cards = match_game.suggest_exchange(&loser).unwrap();
+        match_game.exchange_cards(&loser, best_cards).unwrap();
+        assert_eq!(
+            match_game.exchange_cards(&winner, vec![]),
+            Err(ExchangeError::WrongNumberOfCards { expected: 2, given: 0 })
+        );
+
+        let returned_cards = match_game.suggest_exchange(&winner).unwrap();
+        match_game.exchange_cards(&winner, returned_cards).unwrap();
+
+        assert!(match_game.get_pending_exchanges().is_empty());
+        assert_eq!(match_game.get_game().get_transfers().len(), 2);
+        play_deal(&mut match_game);
+    }
+
+    #[test]
+    fn the_previous_loser_starts_the_next_deal() {
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::PreviousLoser,
+            ..DEFAULT_RULESET
+        };
+        let rules = MatchRules {
+            scoring: ScoringScheme::FinishingPlaces(vec![1, 0, 0]),
+            end: MatchEnd::Deals(3),
+        };
+        let mut match_game = Match::new_with_seed(
+            1, 0, &get_ids(), get_suit_array(), ruleset, rules, 11
+        );
+        play_deal(&mut match_game);
+        let loser = match_game.get_results()[0].finishing_order
+            .last().unwrap().to_string();
+
+        match_game.next_deal().unwrap();
+
+        assert_eq!(match_game.get_game().get_next_player(), Some(loser));
+    }
+
+    #[test]
+    fn the_player_after_the_dealer_starts() {
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::DealersLeft,
+            ..DEFAULT_RULESET
+        };
+        let rules = MatchRules {
+            scoring: ScoringScheme::FinishingPlaces(vec![1, 0, 0]),
+            end: MatchEnd::Deals(3),
+        };
+        let mut match_game = Match::new_with_seed(
+            1, 0, &get_ids(), get_suit_array(), ruleset, rules, 11
+        );
+        let ids = get_ids();
+
+        assert_eq!(match_game.get_dealer(), ids[0]);
+        assert_eq!(
+            match_game.get_game().get_next_player(),
+            Some(ids[1].to_string())
+        );
+
+        play_deal(&mut match_game);
+        match_game.next_deal().unwrap();
+
+        assert_eq!(match_game.get_dealer(), ids[1]);
+        assert_eq!(
+            match_game.get_game().get_next_player(),
+            Some(ids[2].to_string())
+        );
+    }
+}
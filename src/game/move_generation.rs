@@ -0,0 +1,397 @@
+use super::{Hand, HandRules, StraightRule};
+use crate::cards::{Card, PlayedCard, Rank, Suit, get_rank_array, get_suit_array};
+use std::collections::BTreeSet;
+
+/// Every distinct hand that can be made from `cards`, ignoring what is
+/// on the table. Jokers are tried as every card they could stand in for.
+/// Each move's cards are sorted, and a pass is not included
+pub fn get_candidate_moves(
+    cards: &[Card],
+    hand_rules: &HandRules
+) -> Vec<Vec<PlayedCard>> {
+    let naturals: Vec<PlayedCard> = cards.iter()
+        .filter_map(|&c| match c {
+            Card::Standard { rank, suit, .. } => {
+                Some(PlayedCard::new(rank, suit, false))
+            },
+            Card::Joker { .. } => None,
+        })
+        .collect();
+    let jokers = cards.len() - naturals.len();
+
+    let mut moves = BTreeSet::new();
+    let mut add_move = |mut played: Vec<PlayedCard>| {
+        played.sort();
+        if Hand::build_with_rules(played.clone(), hand_rules).is_some() {
+            moves.insert(played);
+        }
+    };
+
+    for single in get_singles(&naturals, jokers) {
+        add_move(vec![single]);
+    }
+
+    for rank in get_rank_array().iter() {
+        for &size in [2, 3, 5].iter() {
+            for (set, _) in get_sets(&naturals, *rank, size, jokers) {
+                add_move(set);
+            }
+        }
+    }
+
+    if hand_rules.two_pair {
+        for two_pair in get_two_pairs(&naturals, jokers) {
+            add_move(two_pair);
+        }
+    }
+
+    for straight in get_straights(&naturals, jokers, hand_rules.straight_rule) {
+        add_move(straight);
+    }
+
+    for flush in get_flushes(&naturals, jokers) {
+        add_move(flush);
+    }
+
+    for full_house in get_full_houses(&naturals, jokers) {
+        add_move(full_house);
+    }
+
+    for four_of_a_kind in get_four_of_a_kinds(&naturals, jokers) {
+        add_move(four_of_a_kind);
+    }
+
+    moves.into_iter().collect()
+}
+
+fn get_singles(naturals: &[PlayedCard], jokers: usize) -> Vec<PlayedCard> {
+    let mut singles = naturals.to_vec();
+    if jokers > 0 {
+        singles.extend(get_all_jokers(|_| true));
+    }
+
+    singles
+}
+
+/// sets of `size` cards of one rank, along with the number of
+/// jokers each one uses
+fn get_sets(
+    naturals: &[PlayedCard],
+    rank: Rank,
+    size: usize,
+    jokers: usize,
+) -> Vec<(Vec<PlayedCard>, usize)> {
+    let of_rank: Vec<PlayedCard> = naturals.iter()
+        .filter(|c| c.get_rank() == rank)
+        .cloned()
+        .collect();
+    let joker_options = get_all_jokers(|c| c.get_rank() == rank);
+
+    let mut sets = vec![];
+    for jokers_used in 0..=jokers.min(size) {
+        if size - jokers_used > of_rank.len() {
+            continue;
+        }
+
+        for natural_cards in get_combinations(&of_rank, size - jokers_used) {
+            for joker_cards in get_multisets(&joker_options, jokers_used) {
+                let mut set = natural_cards.clone();
+                set.extend(joker_cards);
+                sets.push((set, jokers_used));
+            }
+        }
+    }
+
+    sets
+}
+
+fn get_straights(
+    naturals: &[PlayedCard],
+    jokers: usize,
+    straight_rule: StraightRule
+) -> Vec<Vec<PlayedCard>> {
+    let mut straights = vec![];
+
+    for window in straight_rule.get_runs() {
+        let mut partial: Vec<(Vec<PlayedCard>, usize)> = vec![(vec![], 0)];
+
+        for &rank in window.iter() {
+            let mut options: Vec<(PlayedCard, usize)> = naturals.iter()
+                .filter(|c| c.get_rank() == rank)
+                .map(|&c| (c, 0))
+                .collect();
+            options.sort();
+            options.dedup();
+            options.extend(
+                get_all_jokers(|c| c.get_rank() == rank)
+                    .into_iter()
+                    .map(|c| (c, 1))
+            );
+
+            partial = partial.iter()
+                .flat_map(|(cards, used)| {
+                    options.iter()
+                        .filter(move |(_, joker)| used + joker <= jokers)
+                        .map(move |&(card, joker)| {
+                            let mut next = cards.clone();
+                            next.push(card);
+                            (next, used + joker)
+                        })
+                })
+                .collect();
+        }
+
+        straights.extend(partial.into_iter().map(|(cards, _)| cards));
+    }
+
+    straights
+}
+
+fn get_flushes(naturals: &[PlayedCard], jokers: usize) -> Vec<Vec<PlayedCard>> {
+    let mut flushes = vec![];
+
+    for &suit in get_suit_array().iter() {
+        let of_suit: Vec<PlayedCard> = naturals.iter()
+            .filter(|c| c.get_suit() == suit)
+            .cloned()
+            .collect();
+        let joker_options = get_all_jokers(|c| c.get_suit() == suit);
+
+        for jokers_used in 0..=jokers.min(5) {
+            if 5 - jokers_used > of_suit.len() {
+                continue;
+            }
+
+            for natural_cards in get_combinations(&of_suit, 5 - jokers_used) {
+                for joker_cards in get_multisets(&joker_options, jokers_used) {
+                    let mut flush = natural_cards.clone();
+                    flush.extend(joker_cards);
+                    flushes.push(flush);
+                }
+            }
+        }
+    }
+
+    flushes
+}
+
+fn get_full_houses(naturals: &[PlayedCard], jokers: usize) -> Vec<Vec<PlayedCard>> {
+    let mut full_houses = vec![];
+
+    for &prial_rank in get_rank_array().iter() {
+        for (prial, prial_jokers) in get_sets(naturals, prial_rank, 3, jokers) {
+            for &pair_rank in get_rank_array().iter() {
+                if pair_rank == prial_rank {
+                    continue;
+                }
+
+                let pairs = get_sets(
+                    naturals, pair_rank, 2, jokers - prial_jokers
+                );
+                for (pair, _) in pairs {
+                    let mut full_house = prial.clone();
+                    full_house.extend(pair);
+                    full_houses.push(full_house);
+                }
+            }
+        }
+    }
+
+    full_houses
+}
+
+fn get_two_pairs(naturals: &[PlayedCard], jokers: usize) -> Vec<Vec<PlayedCard>> {
+    let ranks = get_rank_array();
+    let mut two_pairs = vec![];
+
+    for (i, &low_rank) in ranks.iter().enumerate() {
+        for (low_pair, low_jokers) in get_sets(naturals, low_rank, 2, jokers) {
+            for &high_rank in ranks[i + 1..].iter() {
+                let pairs = get_sets(
+                    naturals, high_rank, 2, jokers - low_jokers
+                );
+                for (high_pair, _) in pairs {
+                    let mut two_pair = low_pair.clone();
+                    two_pair.extend(high_pair);
+                    two_pairs.push(two_pair);
+                }
+            }
+        }
+    }
+
+    two_pairs
+}
+
+fn get_four_of_a_kinds(
+    naturals: &[PlayedCard],
+    jokers: usize,
+) -> Vec<Vec<PlayedCard>> {
+    let mut four_of_a_kinds = vec![];
+
+    for &rank in get_rank_array().iter() {
+        for (quad, quad_jokers) in get_sets(naturals, rank, 4, jokers) {
+            let mut kickers: Vec<PlayedCard> = naturals.iter()
+                .filter(|c| c.get_rank() != rank)
+                .cloned()
+                .collect();
+            if quad_jokers < jokers {
+                kickers.extend(get_all_jokers(|c| c.get_rank() != rank));
+            }
+
+            for kicker in kickers {
+                let mut four_of_a_kind = quad.clone();
+                four_of_a_kind.push(kicker);
+                four_of_a_kinds.push(four_of_a_kind);
+            }
+        }
+    }
+
+    four_of_a_kinds
+}
+
+fn get_all_jokers<F>(filter: F) -> Vec<PlayedCard>
+where
+    F: Fn(&PlayedCard) -> bool,
+{
+    let suits: [Suit; 4] = get_suit_array();
+
+    get_rank_array().iter()
+        .flat_map(|&rank| {
+            suits.iter().map(move |&suit| PlayedCard::new(rank, suit, true))
+        })
+        .filter(|c| filter(c))
+        .collect()
+}
+
+/// ways of choosing `n` of the given cards, without replacement
+fn get_combinations(cards: &[PlayedCard], n: usize) -> Vec<Vec<PlayedCard>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut combinations = vec![];
+    for (i, &card) in cards.iter().enumerate() {
+        for mut rest in get_combinations(&cards[i + 1..], n - 1) {
+            rest.insert(0, card);
+            combinations.push(rest);
+        }
+    }
+
+    combinations
+}
+
+/// ways of choosing `n` of the given cards, with replacement -
+/// used for jokers, which can all stand in for the same card
+fn get_multisets(cards: &[PlayedCard], n: usize) -> Vec<Vec<PlayedCard>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut multisets = vec![];
+    for (i, &card) in cards.iter().enumerate() {
+        for mut rest in get_multisets(&cards[i..], n - 1) {
+            rest.insert(0, card);
+            multisets.push(rest);
+        }
+    }
+
+    multisets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::DEFAULT_HAND_RULES;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::Standard { deck_id: 0, rank, suit }
+    }
+
+    #[test]
+    fn it_finds_singles_and_pairs() {
+        let cards = vec![
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Three, Suit::Hearts),
+            card(Rank::Six, Suit::Spades),
+        ];
+
+        let moves = get_candidate_moves(&cards, &DEFAULT_HAND_RULES);
+
+        assert_eq!(moves.len(), 4);
+        assert!(moves.contains(&vec![
+            PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            PlayedCard::new(Rank::Three, Suit::Hearts, false),
+        ]));
+    }
+
+    #[test]
+    fn it_finds_five_card_tricks() {
+        let cards = vec![
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Four, Suit::Clubs),
+            card(Rank::Five, Suit::Clubs),
+            card(Rank::Six, Suit::Clubs),
+            card(Rank::Seven, Suit::Clubs),
+            card(Rank::Seven, Suit::Hearts),
+        ];
+
+        let moves = get_candidate_moves(&cards, &DEFAULT_HAND_RULES);
+        let tricks: Vec<&Vec<PlayedCard>> = moves.iter()
+            .filter(|m| m.len() == 5)
+            .collect();
+
+        // a straight flush, a straight using the seven of hearts,
+        // and nothing else
+        assert_eq!(tricks.len(), 2);
+    }
+
+    #[test]
+    fn jokers_can_stand_in_for_any_card() {
+        let cards = vec![
+            card(Rank::Nine, Suit::Spades),
+            Card::Joker { deck_id: 0 },
+        ];
+
+        let moves = get_candidate_moves(&cards, &DEFAULT_HAND_RULES);
+
+        // the nine, 52 joker singles and 4 pairs of nines
+        assert_eq!(moves.len(), 57);
+        assert!(moves.contains(&vec![
+            PlayedCard::new(Rank::Nine, Suit::Clubs, true),
+            PlayedCard::new(Rank::Nine, Suit::Spades, false),
+        ]));
+    }
+
+    #[test]
+    fn identical_cards_from_different_decks_are_one_move() {
+        let cards = vec![
+            Card::Standard { deck_id: 0, rank: Rank::Ten, suit: Suit::Hearts },
+            Card::Standard { deck_id: 1, rank: Rank::Ten, suit: Suit::Hearts },
+        ];
+
+        let moves = get_candidate_moves(&cards, &DEFAULT_HAND_RULES);
+
+        assert_eq!(moves.len(), 2);
+    }
+
+    #[test]
+    fn two_pairs_are_only_found_when_the_rules_allow_them() {
+        let cards = vec![
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Three, Suit::Hearts),
+            card(Rank::Six, Suit::Spades),
+            card(Rank::Six, Suit::Diamonds),
+        ];
+        let two_pair = HandRules { two_pair: true, ..DEFAULT_HAND_RULES };
+
+        let four_card_moves = |hand_rules| {
+            get_candidate_moves(&cards, hand_rules).into_iter()
+                .filter(|m| m.len() == 4)
+                .count()
+        };
+
+        assert_eq!(four_card_moves(&DEFAULT_HAND_RULES), 0);
+        assert_eq!(four_card_moves(&two_pair), 1);
+    }
+}
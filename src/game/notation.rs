@@ -0,0 +1,1078 @@
+use super::{
+    BombRule,
+    CardTransfer,
+    FlushPrecedence,
+    Game,
+    HandRules,
+    Replay,
+    ReplayError,
+    Ruleset,
+    SetupError,
+    StartingRule,
+    StraightRule,
+};
+use crate::cards::{Card, PlayedCard, Suit};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum NotationErrorKind {
+    MalformedTag,
+    UnknownTag(String),
+    DuplicateTag(String),
+    MissingTag(String),
+    InvalidValue(String),
+    MalformedMove,
+    TurnOutOfSequence(usize),
+    UnknownPlayer(String),
+    InvalidCard(String),
+}
+
+/// A record that couldn't be replayed, either before the first
+/// move or at one of its moves
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordError {
+    Setup(SetupError),
+    Replay(ReplayError),
+}
+
+impl From<SetupError> for RecordError {
+    fn from(error: SetupError) -> RecordError {
+        RecordError::Setup(error)
+    }
+}
+
+impl From<ReplayError> for RecordError {
+    fn from(error: ReplayError) -> RecordError {
+        RecordError::Replay(error)
+    }
+}
+
+/// Error parsing game notation - line and column start from 1
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotationError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: NotationErrorKind,
+}
+
+/// A complete game in a form that can be written to and read
+/// from text, e.g.
+///
+/// ```text
+/// [Player "a"]
+/// [Player "b"]
+/// [Decks "1"]
+/// [Jokers "1"]
+/// [Reversals "true"]
+/// [FlushPrecedence "rank"]
+/// [SuitOrder "C H D S"]
+/// [Seed "42"]
+/// [CardExchange "1 0 0 0"]
+/// [StartingRule "previous-winner"]
+/// [Straights "low-ace"]
+/// [Hands "two-pair no-straight-flushes"]
+/// [Bombs "3 four-of-a-kind straight-flush"]
+/// [FlushKickers "true"]
+/// [StartingPlayer "b"]
+/// [Transfer "b -> a: 2S"]
+/// [Transfer "a -> b: 4D@1"]
+///
+/// 1. a: 3C 3H
+/// 2. b: JK=AS 4S
+/// 3. a: pass
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub players: Vec<String>,
+    pub num_decks: u8,
+    pub num_jokers: u8,
+    pub ruleset: Ruleset,
+    pub suit_order: [Suit; 4],
+    /// left out for games whose deal isn't known, which can be
+    /// read and written but not replayed
+    pub seed: Option<u64>,
+    /// cards exchanged after the deal, before the first move
+    pub transfers: Vec<CardTransfer>,
+    /// who was chosen to start, for starting rules that don't go
+    /// by the cards
+    pub starting_player: Option<String>,
+    pub moves: Vec<(String, Vec<PlayedCard>)>,
+}
+
+impl GameRecord {
+    pub fn from_game(game: &Game) -> GameRecord {
+        GameRecord {
+            players: game.get_player_ids(),
+            num_decks: game.get_num_decks(),
+            num_jokers: game.get_num_jokers(),
+            ruleset: game.get_ruleset(),
+            suit_order: game.get_initial_suit_order(),
+            seed: game.get_seed(),
+            transfers: game.get_transfers(),
+            starting_player: game.get_round().get_chosen_starting_player(),
+            moves: game.get_history()
+                .into_iter()
+                .map(|t| (t.player_id, t.hand.to_cards()))
+                .collect(),
+        }
+    }
+
+    pub fn parse(notation: &str) -> Result<GameRecord, NotationError> {
+        let mut players = vec![];
+        let mut num_decks = None;
+        let mut num_jokers = None;
+        let mut reversals_enabled = None;
+        let mut flush_precedence = None;
+        let mut suit_order = None;
+        let mut seed = None;
+        let mut card_exchange = None;
+        let mut starting_rule = None;
+        let mut straight_rule = None;
+        let mut hand_rules = None;
+        let mut bombs = None;
+        let mut flush_kickers = None;
+        let mut starting_player = None;
+        let mut transfers = vec![];
+        let mut moves = vec![];
+        let mut last_line = 0;
+
+        for (i, raw_line) in notation.lines().enumerate() {
+            let line = i + 1;
+            last_line = line;
+            let trimmed = raw_line.trim();
+            let indent = raw_line.len() - raw_line.trim_start().len();
+
+            if trimmed.is_empty() || trimmed.starts_with(';') {
+                continue;
+            }
+
+            if trimmed.starts_with('[') {
+                if !moves.is_empty() {
+                    return Err(error(line, indent + 1, NotationErrorKind::MalformedMove));
+                }
+
+                let (tag, value) = parse_tag(trimmed)
+                    .ok_or_else(|| error(line, indent + 1, NotationErrorKind::MalformedTag))?;
+                let value_column = indent + trimmed.find('"').unwrap_or(0) + 2;
+                let invalid = || error(
+                    line,
+                    value_column,
+                    NotationErrorKind::InvalidValue(value.clone())
+                );
+
+                match tag.as_str() {
+                    "Player" => players.push(value.clone()),
+                    "Decks" => set_once(
+                        &mut num_decks,
+                        value.parse().map_err(|_| invalid())?,
+                        &tag,
+                        line,
+                    )?,
+                    "Jokers" => set_once(
+                        &mut num_jokers,
+                        value.parse().map_err(|_| invalid())?,
+                        &tag,
+                        line,
+                    )?,
+                    "Reversals" => set_once(
+                        &mut reversals_enabled,
+                        value.parse().map_err(|_| invalid())?,
+                        &tag,
+                        line,
+                    )?,
+                    "FlushPrecedence" => set_once(
+                        &mut flush_precedence,
+                        parse_flush_precedence(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "SuitOrder" => set_once(
+                        &mut suit_order,
+                        parse_suit_order(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "Seed" => set_once(
+                        &mut seed,
+                        value.parse().map_err(|_| invalid())?,
+                        &tag,
+                        line,
+                    )?,
+                    "CardExchange" => set_once(
+                        &mut card_exchange,
+                        parse_card_exchange(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "StartingRule" => set_once(
+                        &mut starting_rule,
+                        parse_starting_rule(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "Straights" => set_once(
+                        &mut straight_rule,
+                        parse_straight_rule(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "Hands" => set_once(
+                        &mut hand_rules,
+                        parse_hand_rules(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "Bombs" => set_once(
+                        &mut bombs,
+                        parse_bombs(&value).ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "FlushKickers" => set_once(
+                        &mut flush_kickers,
+                        value.parse().map_err(|_| invalid())?,
+                        &tag,
+                        line,
+                    )?,
+                    "StartingPlayer" => set_once(
+                        &mut starting_player,
+                        Some(value.clone())
+                            .filter(|id| players.contains(id))
+                            .ok_or_else(invalid)?,
+                        &tag,
+                        line,
+                    )?,
+                    "Transfer" => transfers.push(
+                        parse_transfer(&value, &players).ok_or_else(invalid)?
+                    ),
+                    _ => return Err(error(
+                        line,
+                        indent + 2,
+                        NotationErrorKind::UnknownTag(tag)
+                    )),
+                }
+
+                continue;
+            }
+
+            let player_move = parse_move(
+                raw_line,
+                line,
+                moves.len() + 1,
+                &players
+            )?;
+            moves.push(player_move);
+        }
+
+        let missing = |tag: &str| error(
+            last_line + 1,
+            1,
+            NotationErrorKind::MissingTag(tag.to_string())
+        );
+
+        if players.is_empty() {
+            return Err(missing("Player"));
+        }
+
+        Ok(GameRecord {
+            players,
+            num_decks: num_decks.ok_or_else(|| missing("Decks"))?,
+            num_jokers: num_jokers.ok_or_else(|| missing("Jokers"))?,
+            ruleset: Ruleset {
+                reversals_enabled: reversals_enabled
+                    .ok_or_else(|| missing("Reversals"))?,
+                flush_precedence: flush_precedence
+                    .ok_or_else(|| missing("FlushPrecedence"))?,
+                card_exchange: card_exchange.unwrap_or_default(),
+                starting_rule: starting_rule.unwrap_or_default(),
+                hand_rules: HandRules {
+                    straight_rule: straight_rule.unwrap_or_default(),
+                    ..hand_rules.unwrap_or_default()
+                },
+                bombs,
+                flush_kickers: flush_kickers.unwrap_or_default(),
+            },
+            suit_order: suit_order.ok_or_else(|| missing("SuitOrder"))?,
+            seed,
+            transfers,
+            starting_player,
+            moves,
+        })
+    }
+
+    /// Deal the game from its seed, ready to step through the moves
+    pub fn to_replay(&self) -> Result<Replay, SetupError> {
+        let deal = Game::new_with_seed(
+            self.num_decks,
+            self.num_jokers,
+            &self.players,
+            self.suit_order,
+            self.ruleset,
+            self.seed.ok_or(SetupError::MissingSeed)?
+        );
+
+        Replay::from_deal(
+            deal,
+            self.starting_player.clone(),
+            &self.transfers,
+            self.moves.clone()
+        )
+    }
+
+    /// Play every move, returning the final state of the game
+    pub fn to_game(&self) -> Result<Game, RecordError> {
+        let mut replay = self.to_replay()?;
+        let game = replay.seek(self.moves.len())?;
+
+        Ok(game.clone())
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for player in self.players.iter() {
+            writeln!(f, "[Player \"{}\"]", escape(player))?;
+        }
+        writeln!(f, "[Decks \"{}\"]", self.num_decks)?;
+        writeln!(f, "[Jokers \"{}\"]", self.num_jokers)?;
+        writeln!(f, "[Reversals \"{}\"]", self.ruleset.reversals_enabled)?;
+        writeln!(
+            f,
+            "[FlushPrecedence \"{}\"]",
+            format_flush_precedence(self.ruleset.flush_precedence)
+        )?;
+        let suits: Vec<String> = self.suit_order.iter()
+            .map(|s| s.to_string())
+            .collect();
+        writeln!(f, "[SuitOrder \"{}\"]", suits.join(" "))?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "[Seed \"{}\"]", seed)?;
+        }
+        if self.ruleset.card_exchange != [0; 4] {
+            let counts: Vec<String> = self.ruleset.card_exchange.iter()
+                .map(|c| c.to_string())
+                .collect();
+            writeln!(f, "[CardExchange \"{}\"]", counts.join(" "))?;
+        }
+        if self.ruleset.starting_rule != StartingRule::default() {
+            writeln!(
+                f,
+                "[StartingRule \"{}\"]",
+                format_starting_rule(self.ruleset.starting_rule)
+            )?;
+        }
+        let hand_rules = self.ruleset.hand_rules;
+        if hand_rules.straight_rule != StraightRule::default() {
+            writeln!(
+                f,
+                "[Straights \"{}\"]",
+                format_straight_rule(hand_rules.straight_rule)
+            )?;
+        }
+        let hand_options = format_hand_rules(hand_rules);
+        if !hand_options.is_empty() {
+            writeln!(f, "[Hands \"{}\"]", hand_options.join(" "))?;
+        }
+        if self.ruleset.flush_kickers {
+            writeln!(f, "[FlushKickers \"true\"]")?;
+        }
+        if let Some(bombs) = self.ruleset.bombs {
+            writeln!(f, "[Bombs \"{}\"]", format_bombs(bombs))?;
+        }
+        if let Some(player_id) = &self.starting_player {
+            writeln!(f, "[StartingPlayer \"{}\"]", escape(player_id))?;
+        }
+        for transfer in self.transfers.iter() {
+            let cards: Vec<String> = transfer.cards.iter()
+                .map(|&c| format_transfer_card(c))
+                .collect();
+            writeln!(
+                f,
+                "[Transfer \"{}\"]",
+                escape(&format!(
+                    "{} -> {}: {}",
+                    transfer.from,
+                    transfer.to,
+                    cards.join(" ")
+                ))
+            )?;
+        }
+        writeln!(f)?;
+
+        for (i, (player_id, cards)) in self.moves.iter().enumerate() {
+            let tokens: Vec<String> = if cards.is_empty() {
+                vec!["pass".to_string()]
+            } else {
+                cards.iter().map(|&c| format_card(c)).collect()
+            };
+            writeln!(f, "{}. {}: {}", i + 1, player_id, tokens.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn error(line: usize, column: usize, kind: NotationErrorKind) -> NotationError {
+    NotationError { line, column, kind }
+}
+
+fn set_once<T>(
+    field: &mut Option<T>,
+    value: T,
+    tag: &str,
+    line: usize
+) -> Result<(), NotationError> {
+    if field.is_some() {
+        return Err(error(
+            line,
+            1,
+            NotationErrorKind::DuplicateTag(tag.to_string())
+        ));
+    }
+
+    *field = Some(value);
+    Ok(())
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (tag, rest) = inner.split_at(inner.find(' ')?);
+    let quoted = rest.trim();
+    let value = quoted.strip_prefix('"')?.strip_suffix('"')?;
+
+    Some((tag.to_string(), unescape(value)?))
+}
+
+fn parse_move(
+    raw_line: &str,
+    line: usize,
+    expected_turn: usize,
+    players: &[String]
+) -> Result<(String, Vec<PlayedCard>), NotationError> {
+    let malformed = |column| error(line, column, NotationErrorKind::MalformedMove);
+    let indent = raw_line.len() - raw_line.trim_start().len();
+
+    let dot = raw_line.find('.').ok_or_else(|| malformed(indent + 1))?;
+    let turn: usize = raw_line[indent..dot].trim()
+        .parse()
+        .map_err(|_| malformed(indent + 1))?;
+    if turn != expected_turn {
+        return Err(error(
+            line,
+            indent + 1,
+            NotationErrorKind::TurnOutOfSequence(turn)
+        ));
+    }
+
+    let colon = raw_line.rfind(':').ok_or_else(|| malformed(dot + 2))?;
+    if colon < dot {
+        return Err(malformed(dot + 2));
+    }
+    let player_id = raw_line[dot + 1..colon].trim().to_string();
+    if !players.contains(&player_id) {
+        let column = dot + 2 + raw_line[dot + 1..].len()
+            - raw_line[dot + 1..].trim_start().len();
+        return Err(error(
+            line,
+            column,
+            NotationErrorKind::UnknownPlayer(player_id)
+        ));
+    }
+
+    let mut cards = vec![];
+    let mut offset = colon + 1;
+    for token in raw_line[colon + 1..].split_whitespace() {
+        let column = offset + raw_line[offset..].find(token).unwrap() + 1;
+        offset = column - 1 + token.len();
+
+        if token == "pass" {
+            continue;
+        }
+
+        let card = parse_card(token).ok_or_else(|| error(
+            line,
+            column,
+            NotationErrorKind::InvalidCard(token.to_string())
+        ))?;
+        cards.push(card);
+    }
+
+    Ok((player_id, cards))
+}
+
+fn parse_card(token: &str) -> Option<PlayedCard> {
+    token.parse().ok()
+}
+
+fn format_card(card: PlayedCard) -> String {
+    if card.get_is_joker() {
+        format!("JK={}{}", card.get_rank(), card.get_suit())
+    } else {
+        card.to_string()
+    }
+}
+
+/// Transferred cards are the actual cards that changed hands, so
+/// copies from a second deck onwards carry their deck - `4D@1`
+fn parse_transfer_card(token: &str) -> Option<Card> {
+    let (card, deck_id) = match token.find('@') {
+        Some(at) => (&token[..at], token[at + 1..].parse().ok()?),
+        None => (token, 0),
+    };
+
+    match card.parse().ok()? {
+        Card::Standard { rank, suit, .. } => {
+            Some(Card::Standard { deck_id, rank, suit })
+        },
+        Card::Joker { .. } => Some(Card::Joker { deck_id }),
+    }
+}
+
+fn format_transfer_card(card: Card) -> String {
+    match card {
+        Card::Standard { deck_id, .. } | Card::Joker { deck_id }
+            if deck_id > 0 => format!("{}@{}", card, deck_id),
+        _ => card.to_string(),
+    }
+}
+
+fn parse_suit_order(value: &str) -> Option<[Suit; 4]> {
+    let suits: Vec<Suit> = value.split_whitespace()
+        .map(|s| s.parse().ok())
+        .collect::<Option<Vec<Suit>>>()?;
+
+    if suits.len() != 4
+        || suits.iter().enumerate().any(|(i, s)| suits[..i].contains(s)) {
+        return None;
+    }
+
+    Some([suits[0], suits[1], suits[2], suits[3]])
+}
+
+fn parse_card_exchange(value: &str) -> Option<[u8; 4]> {
+    let counts: Vec<u8> = value.split_whitespace()
+        .map(|c| c.parse().ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    if counts.len() != 4 {
+        return None;
+    }
+
+    Some([counts[0], counts[1], counts[2], counts[3]])
+}
+
+/// `from -> to: cards`
+fn parse_transfer(value: &str, players: &[String]) -> Option<CardTransfer> {
+    let colon = value.rfind(':')?;
+    let arrow = value[..colon].find(" -> ")?;
+    let from = value[..arrow].trim().to_string();
+    let to = value[arrow + 4..colon].trim().to_string();
+
+    if !players.contains(&from) || !players.contains(&to) {
+        return None;
+    }
+
+    let cards = value[colon + 1..].split_whitespace()
+        .map(parse_transfer_card)
+        .collect::<Option<Vec<Card>>>()?;
+
+    Some(CardTransfer { from, to, cards })
+}
+
+fn parse_flush_precedence(value: &str) -> Option<FlushPrecedence> {
+    match value {
+        "rank" => Some(FlushPrecedence::Rank),
+        "suit" => Some(FlushPrecedence::Suit),
+        _ => None,
+    }
+}
+
+fn format_flush_precedence(flush_precedence: FlushPrecedence) -> &'static str {
+    match flush_precedence {
+        FlushPrecedence::Rank => "rank",
+        FlushPrecedence::Suit => "suit",
+    }
+}
+
+fn parse_starting_rule(value: &str) -> Option<StartingRule> {
+    match value {
+        "lowest-card" => Some(StartingRule::LowestCard),
+        "lowest-card-not-required" => Some(StartingRule::LowestCardNotRequired),
+        "previous-winner" => Some(StartingRule::PreviousWinner),
+        "previous-loser" => Some(StartingRule::PreviousLoser),
+        "dealers-left" => Some(StartingRule::DealersLeft),
+        _ => None,
+    }
+}
+
+fn format_starting_rule(starting_rule: StartingRule) -> &'static str {
+    match starting_rule {
+        StartingRule::LowestCard => "lowest-card",
+        StartingRule::LowestCardNotRequired => "lowest-card-not-required",
+        StartingRule::PreviousWinner => "previous-winner",
+        StartingRule::PreviousLoser => "previous-loser",
+        StartingRule::DealersLeft => "dealers-left",
+    }
+}
+
+fn parse_straight_rule(value: &str) -> Option<StraightRule> {
+    match value {
+        "no-wrap" => Some(StraightRule::NoWrap),
+        "low-ace" => Some(StraightRule::LowAce),
+        "low-two" => Some(StraightRule::LowTwo),
+        "wrap-around" => Some(StraightRule::WrapAround),
+        _ => None,
+    }
+}
+
+fn format_straight_rule(straight_rule: StraightRule) -> &'static str {
+    match straight_rule {
+        StraightRule::NoWrap => "no-wrap",
+        StraightRule::LowAce => "low-ace",
+        StraightRule::LowTwo => "low-two",
+        StraightRule::WrapAround => "wrap-around",
+    }
+}
+
+/// the hand rules that differ from the defaults, e.g. `two-pair`
+fn parse_hand_rules(value: &str) -> Option<HandRules> {
+    let mut hand_rules = HandRules::default();
+
+    for option in value.split_whitespace() {
+        match option {
+            "two-pair" => hand_rules.two_pair = true,
+            "no-five-of-a-kind" => hand_rules.five_of_a_kind = false,
+            "no-full-house-duplicates" => {
+                hand_rules.full_house_duplicates = false
+            },
+            "no-straight-flushes" => hand_rules.straight_flushes = false,
+            _ => return None,
+        }
+    }
+
+    Some(hand_rules)
+}
+
+fn format_hand_rules(hand_rules: HandRules) -> Vec<&'static str> {
+    let mut options = vec![];
+    if hand_rules.two_pair {
+        options.push("two-pair");
+    }
+    if !hand_rules.five_of_a_kind {
+        options.push("no-five-of-a-kind");
+    }
+    if !hand_rules.full_house_duplicates {
+        options.push("no-full-house-duplicates");
+    }
+    if !hand_rules.straight_flushes {
+        options.push("no-straight-flushes");
+    }
+
+    options
+}
+
+/// the largest hand a bomb beats, then the tricks that are bombs
+fn parse_bombs(value: &str) -> Option<BombRule> {
+    let mut options = value.split_whitespace();
+    let mut bombs = BombRule {
+        four_of_a_kind: false,
+        straight_flush: false,
+        five_of_a_kind: false,
+        beats_up_to: options.next()?.parse().ok()?,
+    };
+
+    for option in options {
+        match option {
+            "four-of-a-kind" => bombs.four_of_a_kind = true,
+            "straight-flush" => bombs.straight_flush = true,
+            "five-of-a-kind" => bombs.five_of_a_kind = true,
+            _ => return None,
+        }
+    }
+
+    Some(bombs)
+}
+
+fn format_bombs(bombs: BombRule) -> String {
+    let mut options = vec![bombs.beats_up_to.to_string()];
+    if bombs.four_of_a_kind {
+        options.push("four-of-a-kind".to_string());
+    }
+    if bombs.straight_flush {
+        options.push("straight-flush".to_string());
+    }
+    if bombs.five_of_a_kind {
+        options.push("five-of-a-kind".to_string());
+    }
+
+    options.join(" ")
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut output = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => output.push(chars.next()?),
+            '"' => return None,
+            _ => output.push(c),
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{ExchangeError, DEFAULT_HAND_RULES, DEFAULT_RULESET};
+
+    fn play_some_moves(game: &mut Game) {
+        for _ in 0..6 {
+            let player = game.get_next_player().unwrap();
+            let cards = game.suggest_move(&player).unwrap();
+            game.play_move(&player, cards).unwrap();
+        }
+    }
+
+    #[test]
+    fn a_game_round_trips_through_notation() {
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 2, &ids, get_suit_array(), DEFAULT_RULESET, 2019
+        );
+        play_some_moves(&mut game);
+
+        let record = GameRecord::from_game(&game);
+        let text = record.to_string();
+        let parsed = GameRecord::parse(&text).unwrap();
+
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.to_string(), text);
+
+        let replayed = parsed.to_game().unwrap();
+        assert_eq!(replayed.get_history(), game.get_history());
+    }
+
+    #[test]
+    fn it_writes_headers_and_moves() {
+        let record = GameRecord {
+            players: vec!["a".to_string(), "b \"bob\"".to_string()],
+            num_decks: 1,
+            num_jokers: 1,
+            ruleset: Ruleset {
+                reversals_enabled: false,
+                flush_precedence: FlushPrecedence::Suit,
+                ..Ruleset::default()
+            },
+            suit_order: [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs],
+            seed: Some(5),
+            transfers: vec![],
+            starting_player: None,
+            moves: vec![
+                ("a".to_string(), vec![
+                    PlayedCard::new(Rank::Three, Suit::Spades, false),
+                    PlayedCard::new(Rank::Ten, Suit::Hearts, false),
+                ]),
+                ("b \"bob\"".to_string(), vec![]),
+                ("a".to_string(), vec![
+                    PlayedCard::new(Rank::Ace, Suit::Spades, true),
+                ]),
+            ],
+        };
+
+        let expected = "[Player \"a\"]\n\
+            [Player \"b \\\"bob\\\"\"]\n\
+            [Decks \"1\"]\n\
+            [Jokers \"1\"]\n\
+            [Reversals \"false\"]\n\
+            [FlushPrecedence \"suit\"]\n\
+            [SuitOrder \"S H D C\"]\n\
+            [Seed \"5\"]\n\
+            \n\
+            1. a: 3S 10H\n\
+            2. b \"bob\": pass\n\
+            3. a: JK=AS\n";
+
+        assert_eq!(record.to_string(), expected);
+        assert_eq!(GameRecord::parse(expected).unwrap(), record);
+    }
+
+    #[test]
+    fn ten_can_be_written_as_t() {
+        assert_eq!(
+            parse_card("TD"),
+            Some(PlayedCard::new(Rank::Ten, Suit::Diamonds, false))
+        );
+    }
+
+    #[test]
+    fn missing_tags_are_reported() {
+        let text = "[Player \"a\"]\n[Decks \"1\"]\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err.kind, NotationErrorKind::MissingTag("Jokers".to_string()));
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn invalid_cards_report_line_and_column() {
+        let text = "[Player \"a\"]\n\n1. a: 3C 1X\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err, NotationError {
+            line: 3,
+            column: 10,
+            kind: NotationErrorKind::InvalidCard("1X".to_string()),
+        });
+    }
+
+    #[test]
+    fn unknown_players_are_rejected() {
+        let text = "[Player \"a\"]\n1. z: 3C\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err, NotationError {
+            line: 2,
+            column: 4,
+            kind: NotationErrorKind::UnknownPlayer("z".to_string()),
+        });
+    }
+
+    #[test]
+    fn turns_must_be_in_sequence() {
+        let text = "[Player \"a\"]\n1. a: 3C\n3. a: 4C\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err.kind, NotationErrorKind::TurnOutOfSequence(3));
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn invalid_tag_values_are_rejected() {
+        let text = "[Player \"a\"]\n[SuitOrder \"C C D S\"]\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err, NotationError {
+            line: 2,
+            column: 13,
+            kind: NotationErrorKind::InvalidValue("C C D S".to_string()),
+        });
+    }
+
+    #[test]
+    fn unknown_tags_are_rejected() {
+        let text = "[Colour \"red\"]\n";
+        let err = GameRecord::parse(text).err().unwrap();
+
+        assert_eq!(err.kind, NotationErrorKind::UnknownTag("Colour".to_string()));
+    }
+
+    #[test]
+    fn illegal_moves_are_reported_when_replayed() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 3
+        );
+        let mut record = GameRecord::from_game(&game);
+        let first_player = game.get_next_player().unwrap();
+        record.moves.push((first_player, vec![]));
+
+        match record.to_game() {
+            Err(RecordError::Replay(err)) => assert_eq!(err.turn, 1),
+            result => panic!("expected a replay error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn records_that_cannot_be_set_up_are_reported() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 3
+        );
+        let card = game.get_player("a").unwrap().get_hand()[0];
+        let mut record = GameRecord::from_game(&game);
+        record.seed = None;
+
+        assert_eq!(
+            record.to_game().err(),
+            Some(RecordError::Setup(SetupError::MissingSeed))
+        );
+        assert!(!record.to_string().contains("[Seed"));
+
+        record.seed = game.get_seed();
+        record.starting_player = Some("z".to_string());
+
+        assert_eq!(
+            record.to_game().err(),
+            Some(RecordError::Setup(
+                SetupError::UnknownStartingPlayer("z".to_string())
+            ))
+        );
+
+        let transfer = CardTransfer {
+            from: "b".to_string(),
+            to: "a".to_string(),
+            cards: vec![card],
+        };
+        record.starting_player = None;
+        record.transfers = vec![transfer.clone()];
+
+        assert_eq!(
+            record.to_replay().err(),
+            Some(SetupError::InvalidTransfer(
+                transfer,
+                ExchangeError::PlayerDoesntHaveCard(vec![card])
+            ))
+        );
+    }
+
+    #[test]
+    fn transfers_keep_the_deck_of_each_card() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            2, 0, &ids, get_suit_array(), DEFAULT_RULESET, 8
+        );
+        let card = *game.get_player("a").unwrap().get_hand().iter()
+            .find(|c| matches!(c, Card::Standard { deck_id: 1, .. }))
+            .unwrap();
+        game.transfer_cards("a", "b", &[card]).unwrap();
+
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains(&format!("[Transfer \"a -> b: {}@1\"]", card)));
+
+        let record = GameRecord::parse(&text).unwrap();
+        assert_eq!(record.transfers, game.get_transfers());
+        assert_eq!(
+            record.to_game().unwrap().get_player("b").unwrap().get_hand(),
+            game.get_player("b").unwrap().get_hand()
+        );
+    }
+
+    #[test]
+    fn exchanged_cards_round_trip_through_notation() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let ruleset = Ruleset {
+            card_exchange: [1, 0, 0, 0],
+            ..DEFAULT_RULESET
+        };
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 8
+        );
+        let card = game.get_player("a").unwrap().get_hand()[0];
+        game.transfer_cards("a", "b", &[card]).unwrap();
+        play_some_moves(&mut game);
+
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains("[CardExchange \"1 0 0 0\"]"));
+        assert!(text.contains(&format!("[Transfer \"a -> b: {}\"]", card)));
+
+        let replayed = GameRecord::parse(&text).unwrap().to_game().unwrap();
+        assert_eq!(replayed.get_history(), game.get_history());
+        assert_eq!(replayed.get_transfers(), game.get_transfers());
+    }
+
+    #[test]
+    fn the_chosen_starting_player_round_trips_through_notation() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::PreviousWinner,
+            ..DEFAULT_RULESET
+        };
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 3
+        );
+        game.set_starting_player("b").unwrap();
+        play_some_moves(&mut game);
+
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains("[StartingRule \"previous-winner\"]"));
+        assert!(text.contains("[StartingPlayer \"b\"]"));
+
+        let record = GameRecord::parse(&text).unwrap();
+        assert_eq!(record.ruleset, ruleset);
+        assert_eq!(record.to_game().unwrap().get_history(), game.get_history());
+    }
+
+    #[test]
+    fn the_straight_rule_is_read_from_its_tag() {
+        let text = "[Player \"a\"]\n[Player \"b\"]\n[Decks \"1\"]\n\
+            [Jokers \"0\"]\n[Reversals \"true\"]\n\
+            [FlushPrecedence \"rank\"]\n[SuitOrder \"C H D S\"]\n\
+            [Seed \"1\"]\n[Straights \"wrap-around\"]\n";
+
+        let record = GameRecord::parse(text).unwrap();
+
+        assert_eq!(
+            record.ruleset.hand_rules.straight_rule,
+            StraightRule::WrapAround
+        );
+        assert!(record.to_string().contains("[Straights \"wrap-around\"]"));
+        assert_eq!(
+            GameRecord::parse(&text.replace("wrap-around", "sideways")),
+            Err(NotationError {
+                line: 9,
+                column: 13,
+                kind: NotationErrorKind::InvalidValue("sideways".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn hand_rules_round_trip_through_notation() {
+        let ruleset = Ruleset {
+            hand_rules: HandRules {
+                two_pair: true,
+                straight_flushes: false,
+                ..DEFAULT_HAND_RULES
+            },
+            ..DEFAULT_RULESET
+        };
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 3
+        );
+        play_some_moves(&mut game);
+
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains("[Hands \"two-pair no-straight-flushes\"]"));
+        assert_eq!(GameRecord::parse(&text).unwrap().ruleset, ruleset);
+        assert!(!GameRecord::from_game(
+            &Game::new_with_seed(1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 3)
+        ).to_string().contains("[Hands"));
+    }
+
+    #[test]
+    fn bombs_and_flush_kickers_round_trip_through_notation() {
+        let ruleset = Ruleset {
+            bombs: Some(BombRule {
+                four_of_a_kind: true,
+                straight_flush: false,
+                five_of_a_kind: true,
+                beats_up_to: 1,
+            }),
+            flush_kickers: true,
+            ..DEFAULT_RULESET
+        };
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), ruleset, 3
+        );
+        play_some_moves(&mut game);
+
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains("[Bombs \"1 four-of-a-kind five-of-a-kind\"]"));
+        assert!(text.contains("[FlushKickers \"true\"]"));
+        assert_eq!(GameRecord::parse(&text).unwrap().ruleset, ruleset);
+        assert!(GameRecord::parse(
+            &text.replace("[Bombs \"1 ", "[Bombs \"")
+        ).is_err());
+    }
+}
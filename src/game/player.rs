@@ -33,14 +33,7 @@ impl Player {
 
     pub fn play_move(&mut self, cards: Vec<PlayedCard>) -> Result<Player, PlayerError> {
         for card in cards.iter() {
-            match self.hand.iter()
-                .position(|&c| {
-                    let played_card = card.to_card();
-
-                    c.get_rank() == played_card.get_rank() &&
-                        c.get_suit() == played_card.get_suit()
-                }) {
-
+            match Self::find_card(&self.hand, *card) {
                 Some(index) => self.hand.remove(index),
                 _ => return Err(PlayerError::PlayerDoesntHaveCard),
             };
@@ -49,9 +42,73 @@ impl Player {
         Ok(self.clone())
     }
 
+    /// The cards from a move that aren't in this player's hand
+    pub fn get_missing_cards(&self, cards: &[PlayedCard]) -> Vec<PlayedCard> {
+        let mut hand = self.hand.clone();
+        let mut missing = vec![];
+
+        for &card in cards.iter() {
+            match Self::find_card(&hand, card) {
+                Some(index) => {
+                    hand.remove(index);
+                },
+                None => missing.push(card),
+            }
+        }
+
+        missing
+    }
+
+    /// Take cards out of the hand. Each card is matched exactly
+    /// first, and then by rank and suit from any deck when its own
+    /// copy isn't held. Returns the cards taken, or the ones that
+    /// were missing
+    pub fn remove_cards(&mut self, cards: &[Card]) -> Result<Vec<Card>, Vec<Card>> {
+        let mut hand = self.hand.clone();
+        let mut removed: Vec<Option<Card>> = cards.iter()
+            .map(|card| {
+                let index = hand.iter().position(|c| c == card)?;
+                Some(hand.remove(index))
+            })
+            .collect();
+        let mut missing = vec![];
+
+        for (&card, taken) in cards.iter().zip(removed.iter_mut()) {
+            if taken.is_some() {
+                continue;
+            }
+            match hand.iter().position(|&c| {
+                c.get_rank() == card.get_rank() && c.get_suit() == card.get_suit()
+            }) {
+                Some(index) => *taken = Some(hand.remove(index)),
+                None => missing.push(card),
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        self.hand = hand;
+        Ok(removed.into_iter().flatten().collect())
+    }
+
+    pub fn add_cards(&mut self, cards: &[Card]) {
+        self.hand.extend_from_slice(cards);
+    }
+
     pub fn has_card(&self, card: Card) -> bool {
         self.hand.contains(&card)
     }
+
+    fn find_card(hand: &[Card], card: PlayedCard) -> Option<usize> {
+        let played_card = card.to_card();
+
+        hand.iter().position(|&c| {
+            c.get_rank() == played_card.get_rank() &&
+                c.get_suit() == played_card.get_suit()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -238,4 +295,26 @@ mod tests {
         assert!(new_player.is_ok());
 
     }
+
+    #[test]
+    fn it_lists_cards_missing_from_the_hand() {
+        let hand = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+        ];
+        let player = Player::new(String::from("id1"), hand);
+
+        let three_of_clubs = PlayedCard::new(Rank::Three, Suit::Clubs, false);
+        let four_of_clubs = PlayedCard::new(Rank::Four, Suit::Clubs, false);
+
+        assert_eq!(
+            player.get_missing_cards(
+                &[three_of_clubs, three_of_clubs, four_of_clubs]
+            ),
+            vec![three_of_clubs, four_of_clubs]
+        );
+    }
 }
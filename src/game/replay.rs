@@ -0,0 +1,388 @@
+use super::{
+    CardTransfer,
+    ExchangeError,
+    Game,
+    Player,
+    Round,
+    Ruleset,
+    SubmitError,
+};
+use crate::cards::{Card, PlayedCard, Suit};
+use serde::{Deserialize, Serialize};
+
+/// The first move in a replay that the engine rejected
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayError {
+    /// turn number of the rejected move, starting from 1
+    pub turn: usize,
+    pub player_id: String,
+    pub error: SubmitError,
+}
+
+/// Why the position before the first move couldn't be rebuilt
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum SetupError {
+    /// the game was saved before seeds were recorded, so its deal
+    /// can't be repeated
+    MissingSeed,
+    UnknownStartingPlayer(String),
+    /// a recorded transfer that can't be made from the deal
+    InvalidTransfer(CardTransfer, ExchangeError),
+}
+
+/// Rebuilds the state of a game at any point from its initial
+/// deal and the moves that were played
+#[derive(Debug, Clone)]
+pub struct Replay {
+    moves: Vec<(String, Vec<PlayedCard>)>,
+    states: Vec<Game>,
+    position: usize,
+}
+
+impl Replay {
+    pub fn new(
+        players: Vec<Player>,
+        ruleset: Ruleset,
+        suit_order: [Suit; 4],
+        moves: Vec<(String, Vec<PlayedCard>)>,
+    ) -> Replay {
+        let all_cards: Vec<Card> = players.iter()
+            .flat_map(|p| p.get_hand())
+            .collect();
+        let num_jokers = all_cards.iter()
+            .filter(|c| c.get_rank().is_none())
+            .count() as u8;
+        let num_decks = all_cards.iter()
+            .map(|c| match c {
+                Card::Standard { deck_id, .. } => deck_id + 1,
+                Card::Joker { .. } => 0,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let initial_game = Game::from_players(
+            num_decks,
+            num_jokers,
+            players,
+            suit_order,
+            ruleset,
+            None
+        );
+
+        Self::from_initial_game(initial_game, moves)
+    }
+
+    /// Replay a game from its seed and recorded history
+    pub fn from_game(game: &Game) -> Result<Replay, SetupError> {
+        let deal = Game::new_with_seed(
+            game.get_num_decks(),
+            game.get_num_jokers(),
+            &game.get_player_ids(),
+            game.get_initial_suit_order(),
+            game.get_ruleset(),
+            game.get_seed().ok_or(SetupError::MissingSeed)?
+        );
+        let moves = game.get_history()
+            .into_iter()
+            .map(|t| (t.player_id, t.hand.to_cards()))
+            .collect();
+
+        Self::from_deal(
+            deal,
+            game.get_round().get_chosen_starting_player(),
+            &game.get_transfers(),
+            moves
+        )
+    }
+
+    /// Replay from a fresh deal, first choosing the starting player
+    /// and making the transfers that came before the first move
+    pub fn from_deal(
+        mut deal: Game,
+        starting_player: Option<String>,
+        transfers: &[CardTransfer],
+        moves: Vec<(String, Vec<PlayedCard>)>,
+    ) -> Result<Replay, SetupError> {
+        if let Some(player_id) = starting_player {
+            deal.set_starting_player(&player_id)
+                .map_err(|_| SetupError::UnknownStartingPlayer(player_id))?;
+        }
+        for transfer in transfers {
+            deal.transfer_cards(&transfer.from, &transfer.to, &transfer.cards)
+                .map_err(|e| SetupError::InvalidTransfer(transfer.clone(), e))?;
+        }
+
+        Ok(Self::from_initial_game(deal, moves))
+    }
+
+    pub fn from_initial_game(
+        initial_game: Game,
+        moves: Vec<(String, Vec<PlayedCard>)>,
+    ) -> Replay {
+        Replay {
+            moves,
+            states: vec![initial_game],
+            position: 0,
+        }
+    }
+
+    /// Number of moves in the replay
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Number of moves applied to reach the current state
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    pub fn get_game(&self) -> &Game {
+        &self.states[self.position]
+    }
+
+    pub fn get_round(&self) -> &Round {
+        self.get_game().get_round()
+    }
+
+    pub fn step_forward(&mut self) -> Result<Option<&Game>, ReplayError> {
+        if self.position >= self.moves.len() {
+            return Ok(None);
+        }
+
+        self.seek(self.position + 1).map(Some)
+    }
+
+    pub fn step_back(&mut self) -> Option<&Game> {
+        if self.position == 0 {
+            return None;
+        }
+
+        self.position -= 1;
+        Some(self.get_game())
+    }
+
+    /// Move to the state after `index` moves have been played
+    pub fn seek(&mut self, index: usize) -> Result<&Game, ReplayError> {
+        let target = index.min(self.moves.len());
+
+        while self.states.len() <= target {
+            let next_state = self.apply_move(self.states.len() - 1)?;
+            self.states.push(next_state);
+        }
+
+        self.position = target;
+        Ok(self.get_game())
+    }
+
+    /// Check every move, returning the first one the engine rejects
+    pub fn validate(&mut self) -> Result<(), ReplayError> {
+        let position = self.position;
+        let result = self.seek(self.moves.len()).map(|_| ());
+        self.position = position.min(self.states.len() - 1);
+
+        result
+    }
+
+    fn apply_move(&self, index: usize) -> Result<Game, ReplayError> {
+        let (player_id, cards) = &self.moves[index];
+        let mut game = self.states[index].clone();
+
+        match game.play_move(player_id, cards.clone()) {
+            Ok(()) => Ok(game),
+            Err(error) => Err(ReplayError {
+                turn: index + 1,
+                player_id: player_id.to_string(),
+                error,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{
+        Hand, HandType, NotHighEnough, DEFAULT_RULESET,
+    };
+
+    fn get_players() -> Vec<Player> {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Clubs,
+            },
+        ];
+        let b_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Five,
+                suit: Suit::Clubs,
+            },
+        ];
+
+        vec![
+            Player::new("a".to_string(), a_cards),
+            Player::new("b".to_string(), b_cards),
+        ]
+    }
+
+    fn get_moves() -> Vec<(String, Vec<PlayedCard>)> {
+        vec![
+            (
+                "a".to_string(),
+                vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)]
+            ),
+            (
+                "b".to_string(),
+                vec![PlayedCard::new(Rank::Four, Suit::Clubs, false)]
+            ),
+            (
+                "a".to_string(),
+                vec![PlayedCard::new(Rank::Six, Suit::Clubs, false)]
+            ),
+        ]
+    }
+
+    #[test]
+    fn it_starts_at_the_initial_deal() {
+        let replay = Replay::new(
+            get_players(),
+            DEFAULT_RULESET,
+            get_suit_array(),
+            get_moves()
+        );
+
+        assert_eq!(replay.get_position(), 0);
+        assert_eq!(replay.len(), 3);
+        assert_eq!(replay.get_round().get_last_move(), None);
+    }
+
+    #[test]
+    fn it_can_step_forward_and_back() {
+        let mut replay = Replay::new(
+            get_players(),
+            DEFAULT_RULESET,
+            get_suit_array(),
+            get_moves()
+        );
+
+        replay.step_forward().unwrap();
+        replay.step_forward().unwrap();
+        assert_eq!(
+            replay.get_round().get_last_move(),
+            Some(Hand::Single(
+                PlayedCard::new(Rank::Four, Suit::Clubs, false)
+            ))
+        );
+
+        replay.step_back();
+        assert_eq!(
+            replay.get_round().get_last_move(),
+            Some(Hand::Single(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false)
+            ))
+        );
+        assert_eq!(replay.get_position(), 1);
+    }
+
+    #[test]
+    fn stepping_past_the_end_does_nothing() {
+        let mut replay = Replay::new(
+            get_players(),
+            DEFAULT_RULESET,
+            get_suit_array(),
+            get_moves()
+        );
+
+        replay.seek(3).unwrap();
+        assert!(replay.step_forward().unwrap().is_none());
+        assert_eq!(replay.get_game().get_winners(), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn it_reports_the_first_illegal_move() {
+        let mut moves = get_moves();
+        moves[1] = (
+            "b".to_string(),
+            vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)]
+        );
+        let mut replay = Replay::new(
+            get_players(),
+            DEFAULT_RULESET,
+            get_suit_array(),
+            moves
+        );
+
+        let err = replay.validate().err().unwrap();
+
+        assert_eq!(err.turn, 2);
+        assert_eq!(err.player_id, "b");
+        assert_eq!(err.error, SubmitError::HandNotHighEnough(NotHighEnough {
+            expected: HandType::Single,
+            played: HandType::Single,
+            top_card: PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            failed_card: Some(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false)
+            ),
+        }));
+        assert_eq!(replay.get_position(), 0);
+    }
+
+    #[test]
+    fn a_game_can_be_replayed_from_its_seed_and_history() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let mut game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 99
+        );
+        let first_player = game.get_next_player().unwrap();
+        game.play_move(
+            &first_player,
+            vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)]
+        ).unwrap();
+        let second_player = game.get_next_player().unwrap();
+        game.play_move(&second_player, vec![]).unwrap();
+
+        let mut replay = Replay::from_game(&game).unwrap();
+        assert!(replay.validate().is_ok());
+
+        let replayed = replay.seek(2).unwrap();
+        assert_eq!(replayed.get_history(), game.get_history());
+        assert_eq!(
+            replayed.get_player(&first_player).unwrap().get_hand(),
+            game.get_player(&first_player).unwrap().get_hand()
+        );
+    }
+
+    #[test]
+    fn games_saved_without_a_seed_cannot_be_replayed() {
+        let ids = ["a".to_string(), "b".to_string()];
+        let game = Game::new_with_seed(
+            1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 99
+        );
+        let mut state = serde_json::to_value(&game).unwrap();
+        state.as_object_mut().unwrap().remove("seed");
+        let old_save: Game = serde_json::from_value(state).unwrap();
+
+        assert_eq!(old_save.get_seed(), None);
+        assert_eq!(
+            Replay::from_game(&old_save).err(),
+            Some(SetupError::MissingSeed)
+        );
+    }
+}
@@ -1,22 +1,74 @@
 use super::{
-    compare_hands,
+    compare_hands_with_ruleset,
+    get_candidate_moves,
+    get_deciding_card,
+    HandOrdering,
     Hand,
+    HandType,
+    InvalidHandReason,
     Player,
     Trick,
     TrickType,
     Ruleset,
+    StartingRule,
 };
 use crate::cards::{Card, PlayedCard, Rank, Suit};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum SubmitError {
     FirstRoundPass,
-    FirstHandMustContainLowestCard,
-    HandNotHighEnough,
+    /// the card that the first hand has to include
+    FirstHandMustContainLowestCard(PlayedCard),
+    HandNotHighEnough(NotHighEnough),
     NotCurrentPlayer,
-    InvalidHand,
-    PlayerDoesntHaveCard,
+    InvalidHand(InvalidHandReason),
+    /// the submitted cards that aren't in the player's hand
+    PlayerDoesntHaveCard(Vec<PlayedCard>),
+    GameOver,
+    UnknownPlayer,
+    NotATrick,
+    /// a flush that ties with the last move - one with the same
+    /// cards, or the same top card when flush kickers are off
+    EqualFlush,
+}
+
+/// the name of the error, e.g. `HandNotHighEnough`
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SubmitError::FirstRoundPass => "FirstRoundPass",
+            SubmitError::FirstHandMustContainLowestCard(_) => {
+                "FirstHandMustContainLowestCard"
+            },
+            SubmitError::HandNotHighEnough(_) => "HandNotHighEnough",
+            SubmitError::NotCurrentPlayer => "NotCurrentPlayer",
+            SubmitError::InvalidHand(_) => "InvalidHand",
+            SubmitError::PlayerDoesntHaveCard(_) => "PlayerDoesntHaveCard",
+            SubmitError::GameOver => "GameOver",
+            SubmitError::UnknownPlayer => "UnknownPlayer",
+            SubmitError::NotATrick => "NotATrick",
+            SubmitError::EqualFlush => "EqualFlush",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Why a hand didn't beat the last move
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotHighEnough {
+    /// type of hand needed to beat the last move - for tricks,
+    /// this trick type or higher
+    pub expected: HandType,
+    pub played: HandType,
+    /// the card that decides the strength of the last move
+    pub top_card: PlayedCard,
+    /// the card from the played hand that didn't beat `top_card`,
+    /// when the hands were the same type
+    pub failed_card: Option<PlayedCard>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,7 +79,16 @@ pub struct Round {
     last_player: Option<String>,
     suit_order: [Suit; 4],
     rank_order: [Rank; 13],
-    ruleset: Ruleset
+    ruleset: Ruleset,
+    /// who starts when the starting rule doesn't go by the cards
+    #[serde(default)]
+    starting_player: Option<String>,
+    /// the move that led to this round won the trick
+    #[serde(default)]
+    cleared_table: bool,
+    /// the move that led to this round reversed the orders
+    #[serde(default)]
+    reversed: bool,
 }
 
 impl Round {
@@ -48,9 +109,34 @@ impl Round {
             suit_order,
             rank_order,
             ruleset,
+            starting_player: None,
+            cleared_table: false,
+            reversed: false,
         }
     }
 
+    /// Choose who starts a round that hasn't started yet - this is
+    /// ignored when the starting rule goes by the lowest card, or
+    /// when the player isn't in the round
+    pub fn set_starting_player(&mut self, player_id: Option<String>) {
+        self.starting_player = player_id;
+    }
+
+    pub fn get_chosen_starting_player(&self) -> Option<String> {
+        self.starting_player.clone()
+    }
+
+    /// The card the first hand must contain, if the starting rule
+    /// requires one. This is the lowest card that was dealt, which
+    /// isn't always the lowest card in the deck
+    pub fn get_required_first_card(&self) -> Option<PlayedCard> {
+        if self.ruleset.starting_rule != StartingRule::LowestCard {
+            return None;
+        }
+
+        self.get_lowest_dealt_card().map(|(_, card)| card)
+    }
+
     pub fn get_next_player(&self) -> Option<String> {
         match &self.next_player {
             None => {
@@ -69,39 +155,12 @@ impl Round {
         user_id: &str,
         cards: Vec<PlayedCard>
     ) -> Result<Round, SubmitError> {
-        if user_id != self.get_next_player()
-            .expect("invalid_player") {
-            return Err(SubmitError::NotCurrentPlayer);
-        }
-
-        let hand = Hand::build(cards.clone());
-        if hand.is_none() {
-            return Err(SubmitError::InvalidHand);
-        }
+        let hand = Some(self.validate_move(user_id, &cards)?);
 
-        if self.last_move == None {
-
-            let starting_move_error = self.check_starting_move(
-                &cards
-            );
-
-            if starting_move_error.is_some() {
-                return Err(starting_move_error.unwrap());
-            }
-
-        } else if self.last_move != Some(Hand::Pass)
-            && hand != Some(Hand::Pass) 
-            && !self.hand_beats_last_move(hand.unwrap()) {
-                return Err(SubmitError::HandNotHighEnough);
-        }
-
-        let mut player = self.get_player(user_id)
-            .expect("invalid player!");
-
-        match player.play_move(cards) {
-            Ok(p) => player = p,
-            _ => return Err(SubmitError::PlayerDoesntHaveCard)
-        }
+        let player = self.get_player(user_id)
+            .ok_or(SubmitError::UnknownPlayer)?
+            .play_move(cards)
+            .expect("player has every card in the move");
 
         let players = self.get_updated_players(&player);
         let new_last_player = if hand == Some(Hand::Pass) {
@@ -110,8 +169,8 @@ impl Round {
             Some(user_id.to_string())
         };
 
-        let ( 
-            new_last_move, next_player
+        let (
+            new_last_move, next_player, cleared_table
         ) = self.get_last_move_and_new_player(
             user_id,
             hand,
@@ -125,11 +184,11 @@ impl Round {
             None
         };
 
-        let (
-            suit_order, rank_order
-        ) = self.get_updated_suit_and_rank_order(hand);
+        let (suit_order, rank_order) =
+            self.get_updated_suit_and_rank_order(hand);
+        let reversed = rank_order != self.rank_order;
 
-        Ok(Self::new(
+        let mut round = Self::new(
             players,
             output_next_player,
             new_last_move,
@@ -137,7 +196,53 @@ impl Round {
             suit_order,
             rank_order,
             self.ruleset
-        ))
+        );
+        round.set_starting_player(self.get_chosen_starting_player());
+        round.cleared_table = cleared_table;
+        round.reversed = reversed;
+
+        Ok(round)
+    }
+
+    /// Whether the move that led to this round won the trick,
+    /// leaving the table clear for the next player
+    pub fn was_table_cleared(&self) -> bool {
+        self.cleared_table
+    }
+
+    /// Whether the move that led to this round reversed the suit
+    /// and rank orders
+    pub fn was_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Every distinct move the player could make right now, weakest
+    /// first. A pass comes first when it is allowed
+    pub fn legal_moves(
+        &self,
+        user_id: &str
+    ) -> Result<Vec<Vec<PlayedCard>>, SubmitError> {
+        let player = self.check_turn(user_id)?;
+
+        let mut candidates = vec![vec![]];
+        candidates.extend(get_candidate_moves(
+            &player.get_hand(),
+            &self.ruleset.hand_rules
+        ));
+
+        let mut moves: Vec<(Hand, Vec<PlayedCard>)> = candidates.into_iter()
+            .filter_map(|cards| {
+                self.validate_move(user_id, &cards)
+                    .ok()
+                    .map(|hand| (hand, cards))
+            })
+            .collect();
+        let ordering = self.get_hand_ordering();
+        moves.sort_by_key(|(hand, cards)| {
+            (ordering.get_strength_key(*hand), cards.clone())
+        });
+
+        Ok(moves.into_iter().map(|(_, cards)| cards).collect())
     }
 
     pub fn get_player(&self, user_id: &str) -> Option<Player> {
@@ -150,15 +255,16 @@ impl Round {
         None
     }
 
+    pub fn get_players(&self) -> Vec<Player> {
+        self.players.clone()
+    }
+
     pub fn get_last_move(&self) -> Option<Hand> {
         self.last_move
     }
 
     pub fn get_last_player(&self) -> Option<String> {
-        match &self.last_player {
-            None => None,
-            Some(x) => Some(x.to_string())
-        }
+        self.last_player.as_ref().map(|x| x.to_string())
     }
 
     pub fn get_suit_order(&self) -> [Suit; 4] {
@@ -169,6 +275,69 @@ impl Round {
         self.rank_order
     }
 
+    fn check_turn(&self, user_id: &str) -> Result<Player, SubmitError> {
+        let player = self.get_player(user_id)
+            .ok_or(SubmitError::UnknownPlayer)?;
+
+        let next_player = self.get_next_player()
+            .ok_or(SubmitError::GameOver)?;
+
+        if user_id != next_player {
+            return Err(SubmitError::NotCurrentPlayer);
+        }
+
+        Ok(player)
+    }
+
+    fn validate_move(
+        &self,
+        user_id: &str,
+        cards: &[PlayedCard]
+    ) -> Result<Hand, SubmitError> {
+        let player = self.check_turn(user_id)?;
+        let hand = self.check_move(cards)?;
+
+        let missing_cards = player.get_missing_cards(cards);
+        if !missing_cards.is_empty() {
+            return Err(SubmitError::PlayerDoesntHaveCard(missing_cards));
+        }
+
+        Ok(hand)
+    }
+
+    /// Whether `cards` make a hand that can be played on the table
+    /// as it stands, whoever holds them
+    pub fn check_move(
+        &self,
+        cards: &[PlayedCard]
+    ) -> Result<Hand, SubmitError> {
+        let hand = Hand::try_build_with_rules(
+            cards.to_vec(),
+            &self.ruleset.hand_rules
+        ).map_err(SubmitError::InvalidHand)?;
+
+        if self.last_move.is_none() {
+
+            if let Some(starting_move_error) = self.check_starting_move(
+                cards
+            ) {
+                return Err(starting_move_error);
+            }
+
+        } else if self.last_move != Some(Hand::Pass)
+            && hand != Hand::Pass
+            && !self.hand_beats_last_move(hand) {
+                if self.is_equal_flush(hand) {
+                    return Err(SubmitError::EqualFlush);
+                }
+                return Err(SubmitError::HandNotHighEnough(
+                    self.explain_not_high_enough(hand)
+                ));
+        }
+
+        Ok(hand)
+    }
+
     fn check_starting_move(
         &self,
         cards:&[PlayedCard]) -> Option<SubmitError> {
@@ -176,27 +345,69 @@ impl Round {
                 return Some(SubmitError::FirstRoundPass);
             }
 
-            if !self.contains_lowest_card(cards.to_vec()) {
-                return Some(
-                    SubmitError::FirstHandMustContainLowestCard
-                );
+            if let Some(card) = self.get_required_first_card() {
+                let contains_card = cards.iter().any(|c| {
+                    c.get_rank() == card.get_rank()
+                        && c.get_suit() == card.get_suit()
+                });
+                if !contains_card {
+                    return Some(
+                        SubmitError::FirstHandMustContainLowestCard(card)
+                    );
+                }
             }
 
             None
     }
 
     fn get_starting_player(&self) -> Option<String> {
-        let lowest_card = Card::Standard {
-            deck_id: 0,
-            suit: self.suit_order[0],
-            rank: self.rank_order[0],
-        };
-        for player in self.players.iter() {
-            if player.has_card(lowest_card) {
-                return Some(player.get_id().to_string());
-            }
+        match self.ruleset.starting_rule {
+            StartingRule::LowestCard
+                | StartingRule::LowestCardNotRequired => (),
+            _ => {
+                let chosen_player = self.starting_player.as_ref()
+                    .and_then(|id| self.get_player(id));
+                if let Some(player) = chosen_player {
+                    return Some(player.get_id().to_string());
+                }
+            },
         }
-        None
+
+        self.get_lowest_dealt_card()
+            .map(|(player_id, _)| player_id)
+            .or_else(|| {
+                self.get_players_still_in(&self.players)
+                    .first()
+                    .map(|p| p.get_id().to_string())
+            })
+    }
+
+    /// The lowest natural card in any hand, and who holds it - where
+    /// there are copies from several decks, the first deck's counts
+    fn get_lowest_dealt_card(&self) -> Option<(String, PlayedCard)> {
+        let position = |card: &Card| match *card {
+            Card::Standard { deck_id, rank, suit } => Some((
+                self.rank_order.iter().position(|&r| r == rank),
+                self.suit_order.iter().position(|&s| s == suit),
+                deck_id,
+            )),
+            Card::Joker { .. } => None,
+        };
+
+        self.players.iter()
+            .flat_map(|p| {
+                p.get_hand().into_iter().map(move |c| (p.get_id(), c))
+            })
+            .filter_map(|(id, c)| position(&c).map(|key| (key, id, c)))
+            .min_by_key(|(key, _, _)| *key)
+            .map(|(_, id, c)| (
+                id.to_string(),
+                PlayedCard::new(
+                    c.get_rank().expect("jokers are filtered out"),
+                    c.get_suit().expect("jokers are filtered out"),
+                    false
+                )
+            ))
     }
 
     fn get_updated_players(
@@ -212,24 +423,54 @@ impl Round {
     }
 
     fn hand_beats_last_move(&self, cards: Hand) -> bool {
-        compare_hands(
+        compare_hands_with_ruleset(
             self.last_move
                 .expect("cannot compare when no last_move"),
             cards,
-            self.ruleset.flush_precedence,
+            &self.ruleset,
             self.suit_order,
             self.rank_order,
         )
     }
 
-    fn contains_lowest_card(&self, cards: Vec<PlayedCard>) -> bool {
-        for &card in cards.iter() {
-            if card.get_rank() == self.rank_order[0] && card.get_suit() == self.suit_order[0] {
-                return true;
-            }
-        }
+    fn get_hand_ordering(&self) -> HandOrdering {
+        HandOrdering::from_ruleset(
+            &self.ruleset, self.suit_order, self.rank_order
+        )
+    }
+
+    fn is_equal_flush(&self, hand: Hand) -> bool {
+        let last_move = self.last_move
+            .expect("cannot compare when no last_move");
+        let is_flush = matches!(
+            hand.get_type(),
+            HandType::FiveCardTrick(TrickType::Flush)
+                | HandType::FiveCardTrick(TrickType::StraightFlush)
+        );
 
-        false
+        is_flush && self.get_hand_ordering().compare(hand, last_move)
+            == Some(Ordering::Equal)
+    }
+
+    fn explain_not_high_enough(&self, hand: Hand) -> NotHighEnough {
+        let last_move = self.last_move
+            .expect("cannot compare when no last_move");
+        let expected = last_move.get_type();
+        let played = hand.get_type();
+        let same_type = expected == played;
+
+        NotHighEnough {
+            expected,
+            played,
+            top_card: get_deciding_card(
+                last_move, self.suit_order, self.rank_order
+            ).expect("last move has cards"),
+            failed_card: if same_type {
+                get_deciding_card(hand, self.suit_order, self.rank_order)
+            } else {
+                None
+            },
+        }
     }
 
     fn get_next_player_in_rotation(&self, user_id: &str) -> String {
@@ -259,9 +500,10 @@ impl Round {
             user_id: &str,
             hand: Option<Hand>,
             new_last_player: &Option<String>
-    ) -> (Option<Hand>, String) {
+    ) -> (Option<Hand>, String, bool) {
 
         let mut new_last_move = hand;
+        let mut cleared_table = false;
         let mut next_player = self.get_next_player_in_rotation(
             user_id
         );
@@ -273,6 +515,7 @@ impl Round {
         if next_player == new_last_player.clone()
             .unwrap_or_else(|| "invalid_player".to_string()) {
             new_last_move = Some(Hand::Pass);
+            cleared_table = true;
         }
 
         while self.get_player(&next_player)
@@ -282,27 +525,28 @@ impl Round {
             if next_player == new_last_player.clone()
                 .unwrap_or_else(|| "invalid_player".to_string()) {
                 new_last_move = Some(Hand::Pass);
+                cleared_table = true;
             }
         }
 
-        (new_last_move, next_player)
+        (new_last_move, next_player, cleared_table)
     }
 
     fn get_updated_suit_and_rank_order(
         &self,
-        hand:Option<Hand>
-    ) -> ([Suit;4], [Rank;13]) {
+        hand: Option<Hand>
+    ) -> ([Suit; 4], [Rank; 13]) {
         let mut suit_order = self.suit_order;
         let mut rank_order = self.rank_order;
 
         if self.ruleset.reversals_enabled {
-            if let Hand::FiveCardTrick(Trick{
-                    trick_type: TrickType::FourOfAKind,
-                    ..
-                }) = hand.unwrap_or(Hand::Pass) {
+            if let Hand::FiveCardTrick(Trick {
+                trick_type: TrickType::FourOfAKind,
+                ..
+            }) = hand.unwrap_or(Hand::Pass) {
                 suit_order.reverse();
                 rank_order.reverse();
-            } 
+            }
         }
 
         (suit_order, rank_order)
@@ -314,7 +558,9 @@ impl Round {
 mod tests {
     use super::*;
     use crate::cards::*;
-    use crate::game::FlushPrecedence;
+    use crate::game::{
+        BombRule, HandRules, StartingRule, DEFAULT_HAND_RULES, DEFAULT_RULESET,
+    };
 
     static DEFAULT_SUIT_ORDER: [Suit; 4] =
         [Suit::Clubs, Suit::Hearts, Suit::Diamonds, Suit::Spades];
@@ -335,11 +581,6 @@ mod tests {
         Rank::Two,
     ];
 
-    const DEFAULT_RULESET: Ruleset = Ruleset{
-        reversals_enabled: true,
-        flush_precedence: FlushPrecedence::Rank,
-    };
-
     #[test]
     fn when_game_hasnt_started_player_with_3clubs_starts() {
         let a_cards = vec![Card::Standard {
@@ -466,7 +707,9 @@ mod tests {
             .err().unwrap();
         assert_eq!(
             err,
-            SubmitError::FirstHandMustContainLowestCard
+            SubmitError::FirstHandMustContainLowestCard(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false)
+            )
         );
     }
 
@@ -548,7 +791,14 @@ mod tests {
         ];
 
         let err = round.submit_move("a", played_hand).err().unwrap();
-        assert_eq!(err, SubmitError::HandNotHighEnough);
+        assert_eq!(err, SubmitError::HandNotHighEnough(NotHighEnough {
+            expected: HandType::Single,
+            played: HandType::Single,
+            top_card: PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            failed_card: Some(
+                PlayedCard::new(Rank::Three, Suit::Clubs, false)
+            ),
+        }));
     }
 
     #[test]
@@ -639,6 +889,56 @@ mod tests {
         assert_eq!(err, SubmitError::NotCurrentPlayer);
     }
 
+    #[test]
+    fn a_hand_of_the_wrong_type_cannot_beat_last_move() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Hearts,
+            },
+        ];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let players = vec![player_a, player_b];
+        let last_move = Some(Hand::Single(PlayedCard::new(
+            Rank::Three,
+            Suit::Clubs,
+            false,
+        )));
+        let round = Round::new(
+            players,
+            Some("a".to_string()),
+            last_move,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET
+        );
+        let played_hand = vec![
+            PlayedCard::new(Rank::Six, Suit::Clubs, false),
+            PlayedCard::new(Rank::Six, Suit::Hearts, false),
+        ];
+
+        let err = round.submit_move("a", played_hand).err().unwrap();
+        assert_eq!(err, SubmitError::HandNotHighEnough(NotHighEnough {
+            expected: HandType::Single,
+            played: HandType::Pair,
+            top_card: PlayedCard::new(Rank::Three, Suit::Clubs, false),
+            failed_card: None,
+        }));
+    }
+
     #[test]
     fn it_should_be_a_valid_hand() {
         let a_cards = vec![
@@ -682,7 +982,12 @@ mod tests {
 
         let err = round.submit_move("a", played_hand)
             .err().unwrap();
-        assert_eq!(err, SubmitError::InvalidHand);
+        assert_eq!(
+            err,
+            SubmitError::InvalidHand(InvalidHandReason::MismatchedRanks(
+                vec![Rank::Six, Rank::Three]
+            ))
+        );
     }
 
     #[test]
@@ -723,7 +1028,12 @@ mod tests {
 
         let err = round.submit_move("a", played_hand)
             .err().unwrap();
-        assert_eq!(err, SubmitError::InvalidHand);
+        assert_eq!(
+            err,
+            SubmitError::InvalidHand(InvalidHandReason::MismatchedRanks(
+                vec![Rank::Six, Rank::Three]
+            ))
+        );
     }
 
     #[test]
@@ -769,7 +1079,12 @@ mod tests {
         let err = round.submit_move("a", played_hand)
             .err().unwrap();
 
-        assert_eq!(err, SubmitError::PlayerDoesntHaveCard);
+        assert_eq!(
+            err,
+            SubmitError::PlayerDoesntHaveCard(vec![
+                PlayedCard::new(Rank::Four, Suit::Clubs, false)
+            ])
+        );
 
     }
 
@@ -826,7 +1141,12 @@ mod tests {
         let err = round.submit_move("a", played_hand)
             .err().unwrap();
 
-        assert_eq!(err, SubmitError::PlayerDoesntHaveCard);
+        assert_eq!(
+            err,
+            SubmitError::PlayerDoesntHaveCard(vec![
+                PlayedCard::new(Rank::Six, Suit::Clubs, false)
+            ])
+        );
 
     }
 
@@ -1460,6 +1780,61 @@ mod tests {
 
     }
 
+    #[test]
+    fn moves_after_the_game_is_over_are_rejected() {
+        let a_cards = vec![];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let players = vec![player_a, player_b];
+        let round = Round::new(
+            players,
+            None,
+            Some(Hand::Pass),
+            Some("a".to_string()),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET
+        );
+        let played_hand = vec![
+            PlayedCard::new(Rank::Four, Suit::Clubs, false)
+        ];
+
+        let err = round.submit_move("b", played_hand)
+            .err().unwrap();
+        assert_eq!(err, SubmitError::GameOver);
+    }
+
+    #[test]
+    fn unknown_players_are_rejected() {
+        let a_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Three,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let round = Round::new(
+            vec![player_a],
+            Some("a".to_string()),
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET
+        );
+        let played_hand = vec![
+            PlayedCard::new(Rank::Three, Suit::Clubs, false)
+        ];
+
+        let err = round.submit_move("z", played_hand)
+            .err().unwrap();
+        assert_eq!(err, SubmitError::UnknownPlayer);
+    }
+
     #[test]
     fn when_player_wins_next_player_starts() {
         let a_cards = vec![
@@ -1879,7 +2254,7 @@ mod tests {
 
         let ruleset = Ruleset {
             reversals_enabled: false,
-            flush_precedence: FlushPrecedence::Rank
+            ..Ruleset::default()
         };
 
         let round = Round::new(
@@ -1953,7 +2328,7 @@ mod tests {
         let last_move = Some(Hand::Pass);
         let ruleset = Ruleset {
             reversals_enabled: false,
-            flush_precedence: FlushPrecedence::Rank
+            ..Ruleset::default()
         };
 
         let round = Round::new(
@@ -1978,4 +2353,465 @@ mod tests {
         assert!(new_round.is_ok());
     }
 
+    #[test]
+    fn legal_moves_must_include_the_lowest_card_at_the_start() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Hearts,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Spades,
+            },
+        ];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let round = Round::new(
+            vec![player_a, player_b],
+            None,
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET
+        );
+
+        assert_eq!(
+            round.legal_moves("a").unwrap(),
+            vec![
+                vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)],
+                vec![
+                    PlayedCard::new(Rank::Three, Suit::Clubs, false),
+                    PlayedCard::new(Rank::Three, Suit::Hearts, false),
+                ],
+            ]
+        );
+        assert_eq!(
+            round.legal_moves("b").err().unwrap(),
+            SubmitError::NotCurrentPlayer
+        );
+    }
+
+    #[test]
+    fn legal_moves_are_ordered_by_strength() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Spades,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Hearts,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Five,
+                suit: Suit::Diamonds,
+            },
+        ];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let last_move = Some(Hand::Single(PlayedCard::new(
+            Rank::Four,
+            Suit::Clubs,
+            false,
+        )));
+        let round = Round::new(
+            vec![player_a, player_b],
+            Some("a".to_string()),
+            last_move,
+            Some("b".to_string()),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET
+        );
+
+        assert_eq!(
+            round.legal_moves("a").unwrap(),
+            vec![
+                vec![],
+                vec![PlayedCard::new(Rank::Five, Suit::Diamonds, false)],
+                vec![PlayedCard::new(Rank::Six, Suit::Hearts, false)],
+                vec![PlayedCard::new(Rank::Six, Suit::Spades, false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn legal_moves_follow_reversed_orders() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Spades,
+            },
+        ];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let last_move = Some(Hand::Single(PlayedCard::new(
+            Rank::Five,
+            Suit::Diamonds,
+            false,
+        )));
+        let mut suit_order = DEFAULT_SUIT_ORDER;
+        let mut rank_order = DEFAULT_RANK_ORDER;
+        suit_order.reverse();
+        rank_order.reverse();
+        let round = Round::new(
+            vec![player_a, player_b],
+            Some("a".to_string()),
+            last_move,
+            Some("b".to_string()),
+            suit_order,
+            rank_order,
+            DEFAULT_RULESET
+        );
+
+        assert_eq!(
+            round.legal_moves("a").unwrap(),
+            vec![
+                vec![],
+                vec![PlayedCard::new(Rank::Three, Suit::Clubs, false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn the_lowest_card_can_be_kept_when_not_required() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Three,
+                suit: Suit::Clubs,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Clubs,
+            },
+        ];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let players = vec![player_a, player_b];
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::LowestCardNotRequired,
+            ..DEFAULT_RULESET
+        };
+        let round = Round::new(
+            players,
+            None,
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+
+        assert_eq!(round.get_next_player(), Some("a".to_string()));
+        assert_eq!(round.get_required_first_card(), None);
+        let played_hand = vec![
+            PlayedCard::new(Rank::Six, Suit::Clubs, false)
+        ];
+        assert!(round.submit_move("a", played_hand).is_ok());
+    }
+
+    #[test]
+    fn a_chosen_player_starts_when_the_rule_allows_it() {
+        let a_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Three,
+            suit: Suit::Clubs,
+        }];
+        let b_cards = vec![Card::Standard {
+            deck_id: 0,
+            rank: Rank::Four,
+            suit: Suit::Clubs,
+        }];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let players = vec![player_a, player_b];
+        let ruleset = Ruleset {
+            starting_rule: StartingRule::PreviousWinner,
+            ..DEFAULT_RULESET
+        };
+        let mut round = Round::new(
+            players.clone(),
+            None,
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+        assert_eq!(round.get_next_player(), Some("a".to_string()));
+
+        round.set_starting_player(Some("b".to_string()));
+        assert_eq!(round.get_next_player(), Some("b".to_string()));
+        let played_hand = vec![
+            PlayedCard::new(Rank::Four, Suit::Clubs, false)
+        ];
+        assert!(round.submit_move("b", played_hand).is_ok());
+
+        let mut lowest_card_round = Round::new(
+            players,
+            None,
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET,
+        );
+        lowest_card_round.set_starting_player(Some("b".to_string()));
+        assert_eq!(
+            lowest_card_round.get_next_player(),
+            Some("a".to_string())
+        );
+    }
+
+    #[test]
+    fn the_lowest_dealt_card_starts_when_the_lowest_card_is_missing() {
+        let a_cards = vec![
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Four,
+                suit: Suit::Hearts,
+            },
+            Card::Standard {
+                deck_id: 0,
+                rank: Rank::Six,
+                suit: Suit::Clubs,
+            },
+        ];
+        let b_cards = vec![
+            Card::Joker { deck_id: 0 },
+            Card::Standard {
+                deck_id: 1,
+                rank: Rank::Four,
+                suit: Suit::Clubs,
+            },
+        ];
+        let player_a = Player::new("a".to_string(), a_cards);
+        let player_b = Player::new("b".to_string(), b_cards);
+        let players = vec![player_a, player_b];
+        let round = Round::new(
+            players,
+            None,
+            None,
+            None,
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            DEFAULT_RULESET,
+        );
+
+        let lowest_card = PlayedCard::new(Rank::Four, Suit::Clubs, false);
+        assert_eq!(round.get_next_player(), Some("b".to_string()));
+        assert_eq!(round.get_required_first_card(), Some(lowest_card));
+
+        let joker = PlayedCard::new(Rank::Four, Suit::Diamonds, true);
+        assert_eq!(
+            round.submit_move("b", vec![joker]).err().unwrap(),
+            SubmitError::FirstHandMustContainLowestCard(lowest_card)
+        );
+        assert!(round.submit_move("b", vec![lowest_card]).is_ok());
+    }
+
+    #[test]
+    fn two_pair_can_be_played_when_the_ruleset_allows_it() {
+        let card = |rank, suit| Card::Standard { deck_id: 0, rank, suit };
+        let a_cards = vec![
+            card(Rank::Five, Suit::Clubs),
+            card(Rank::Five, Suit::Hearts),
+            card(Rank::Nine, Suit::Clubs),
+            card(Rank::Nine, Suit::Spades),
+            card(Rank::King, Suit::Clubs),
+        ];
+        let b_cards = vec![card(Rank::Four, Suit::Clubs)];
+        let last_move = Hand::build_with_rules(
+            vec![
+                PlayedCard::new(Rank::Four, Suit::Hearts, false),
+                PlayedCard::new(Rank::Four, Suit::Spades, false),
+                PlayedCard::new(Rank::Eight, Suit::Clubs, false),
+                PlayedCard::new(Rank::Eight, Suit::Hearts, false),
+            ],
+            &HandRules { two_pair: true, ..DEFAULT_HAND_RULES }
+        );
+        let round = |ruleset| Round::new(
+            vec![
+                Player::new("a".to_string(), a_cards.clone()),
+                Player::new("b".to_string(), b_cards.clone()),
+            ],
+            Some("a".to_string()),
+            last_move,
+            Some("b".to_string()),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+        let two_pair = vec![
+            PlayedCard::new(Rank::Five, Suit::Clubs, false),
+            PlayedCard::new(Rank::Five, Suit::Hearts, false),
+            PlayedCard::new(Rank::Nine, Suit::Clubs, false),
+            PlayedCard::new(Rank::Nine, Suit::Spades, false),
+        ];
+
+        assert_eq!(
+            round(DEFAULT_RULESET).submit_move("a", two_pair.clone())
+                .err().unwrap(),
+            SubmitError::InvalidHand(
+                InvalidHandReason::UnsupportedCardCount(4)
+            )
+        );
+
+        let ruleset = Ruleset {
+            hand_rules: HandRules { two_pair: true, ..DEFAULT_HAND_RULES },
+            ..DEFAULT_RULESET
+        };
+        assert!(round(ruleset).submit_move("a", two_pair).is_ok());
+    }
+
+    #[test]
+    fn a_bomb_beats_a_smaller_hand_and_still_reverses_orders() {
+        let card = |rank, suit| Card::Standard { deck_id: 0, rank, suit };
+        let a_cards = vec![
+            card(Rank::Nine, Suit::Clubs),
+            card(Rank::Nine, Suit::Hearts),
+            card(Rank::Nine, Suit::Diamonds),
+            card(Rank::Nine, Suit::Spades),
+            card(Rank::Four, Suit::Clubs),
+            card(Rank::King, Suit::Clubs),
+        ];
+        let b_cards = vec![card(Rank::Three, Suit::Clubs)];
+        let last_move = Some(Hand::Pair(
+            PlayedCard::new(Rank::Two, Suit::Hearts, false),
+            PlayedCard::new(Rank::Two, Suit::Spades, false),
+        ));
+        let round = |ruleset| Round::new(
+            vec![
+                Player::new("a".to_string(), a_cards.clone()),
+                Player::new("b".to_string(), b_cards.clone()),
+            ],
+            Some("a".to_string()),
+            last_move,
+            Some("b".to_string()),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+        let bomb = vec![
+            PlayedCard::new(Rank::Nine, Suit::Clubs, false),
+            PlayedCard::new(Rank::Nine, Suit::Hearts, false),
+            PlayedCard::new(Rank::Nine, Suit::Diamonds, false),
+            PlayedCard::new(Rank::Nine, Suit::Spades, false),
+            PlayedCard::new(Rank::Four, Suit::Clubs, false),
+        ];
+
+        assert!(round(DEFAULT_RULESET).submit_move("a", bomb.clone()).is_err());
+
+        let ruleset = Ruleset {
+            bombs: Some(BombRule {
+                four_of_a_kind: true,
+                straight_flush: true,
+                five_of_a_kind: true,
+                beats_up_to: 3,
+            }),
+            ..DEFAULT_RULESET
+        };
+        let new_round = round(ruleset).submit_move("a", bomb).unwrap();
+
+        let mut expected_rank_order = DEFAULT_RANK_ORDER;
+        expected_rank_order.reverse();
+        assert_eq!(new_round.get_rank_order(), expected_rank_order);
+    }
+
+    #[test]
+    fn a_flush_that_ties_with_the_last_move_is_an_equal_flush() {
+        let card = |deck_id, rank| Card::Standard {
+            deck_id,
+            rank,
+            suit: Suit::Hearts,
+        };
+        let a_cards = vec![
+            card(1, Rank::Four),
+            card(1, Rank::Six),
+            card(1, Rank::Eight),
+            card(1, Rank::Ten),
+            card(1, Rank::King),
+        ];
+        let b_cards = vec![card(0, Rank::Three)];
+        let last_move = Hand::build(vec![
+            PlayedCard::new(Rank::Three, Suit::Hearts, false),
+            PlayedCard::new(Rank::Six, Suit::Hearts, false),
+            PlayedCard::new(Rank::Eight, Suit::Hearts, false),
+            PlayedCard::new(Rank::Ten, Suit::Hearts, false),
+            PlayedCard::new(Rank::King, Suit::Hearts, false),
+        ]);
+        let round = |ruleset| Round::new(
+            vec![
+                Player::new("a".to_string(), a_cards.clone()),
+                Player::new("b".to_string(), b_cards.clone()),
+            ],
+            Some("a".to_string()),
+            last_move,
+            Some("b".to_string()),
+            DEFAULT_SUIT_ORDER,
+            DEFAULT_RANK_ORDER,
+            ruleset,
+        );
+        let flush: Vec<PlayedCard> = a_cards.iter()
+            .map(|c| PlayedCard::new(
+                c.get_rank().unwrap(),
+                c.get_suit().unwrap(),
+                false
+            ))
+            .collect();
+
+        assert_eq!(
+            round(DEFAULT_RULESET).submit_move("a", flush.clone())
+                .err().unwrap(),
+            SubmitError::EqualFlush
+        );
+
+        let ruleset = Ruleset { flush_kickers: true, ..DEFAULT_RULESET };
+        assert!(round(ruleset).submit_move("a", flush).is_ok());
+    }
 }
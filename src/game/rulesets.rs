@@ -1,3 +1,5 @@
+use super::{Hand, TrickType};
+use crate::cards::{get_rank_array, Rank};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
@@ -6,9 +8,202 @@ pub enum FlushPrecedence {
     Rank
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// Who makes the first move of a deal
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StartingRule {
+    /// the holder of the lowest card starts, and must play it
+    #[default]
+    LowestCard,
+    /// the holder of the lowest card starts with any hand
+    LowestCardNotRequired,
+    /// the winner of the previous deal starts
+    PreviousWinner,
+    /// the last placed player of the previous deal starts
+    PreviousLoser,
+    /// the player after the dealer starts
+    DealersLeft,
+}
+
+/// Which runs of ranks count as straights. Each rule allows
+/// everything the one before it does
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StraightRule {
+    /// 3-4-5-6-7 up to J-Q-K-A-2
+    #[default]
+    NoWrap,
+    /// A-2-3-4-5 as well
+    LowAce,
+    /// A-2-3-4-5 and 2-3-4-5-6 as well
+    LowTwo,
+    /// any five ranks in a row, wrapping from 2 back to 3
+    WrapAround,
+}
+
+impl StraightRule {
+    /// The ranks of every allowed straight, lowest rank of the run first
+    pub fn get_runs(self) -> Vec<[Rank; 5]> {
+        let ranks = get_rank_array();
+        let starts: Vec<usize> = match self {
+            StraightRule::NoWrap => (0..9).collect(),
+            StraightRule::LowAce => (0..9).chain(vec![11]).collect(),
+            StraightRule::LowTwo => (0..9).chain(vec![11, 12]).collect(),
+            StraightRule::WrapAround => (0..13).collect(),
+        };
+
+        starts.into_iter()
+            .map(|start| {
+                [0, 1, 2, 3, 4].map(|i| ranks[(start + i) % ranks.len()])
+            })
+            .collect()
+    }
+}
+
+/// Which hands can be played
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandRules {
+    pub straight_rule: StraightRule,
+    pub five_of_a_kind: bool,
+    /// two pairs of different ranks, played as a four card hand
+    pub two_pair: bool,
+    /// a full house can hold two copies of the same card, which
+    /// is only possible with more than one deck
+    pub full_house_duplicates: bool,
+    /// when false, a straight flush only counts as a flush
+    pub straight_flushes: bool,
+}
+
+pub const DEFAULT_HAND_RULES: HandRules = HandRules {
+    straight_rule: StraightRule::NoWrap,
+    five_of_a_kind: true,
+    two_pair: false,
+    full_house_duplicates: true,
+    straight_flushes: true,
+};
+
+impl Default for HandRules {
+    fn default() -> HandRules {
+        DEFAULT_HAND_RULES
+    }
+}
+
+/// Five card tricks that can be played over smaller hands
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BombRule {
+    pub four_of_a_kind: bool,
+    pub straight_flush: bool,
+    pub five_of_a_kind: bool,
+    /// bombs can be played over hands of up to this many cards -
+    /// 1 for singles only, 3 for anything up to a prial
+    pub beats_up_to: usize,
+}
+
+impl BombRule {
+    pub fn is_bomb(self, trick_type: TrickType) -> bool {
+        match trick_type {
+            TrickType::FourOfAKind => self.four_of_a_kind,
+            TrickType::StraightFlush => self.straight_flush,
+            TrickType::FiveOfAKind => self.five_of_a_kind,
+            _ => false,
+        }
+    }
+
+    /// Whether `new_hand` can be played over `last_move` as a bomb.
+    /// A bomb played over another five card trick has to beat it
+    /// the usual way
+    pub fn can_bomb(self, last_move: Hand, new_hand: Hand) -> bool {
+        let size = last_move.to_cards().len();
+
+        match new_hand {
+            Hand::FiveCardTrick(trick) => {
+                self.is_bomb(trick.trick_type)
+                    && size > 0
+                    && size < 5
+                    && size <= self.beats_up_to
+            },
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ruleset {
     pub reversals_enabled: bool,
-    pub flush_precedence: FlushPrecedence
+    pub flush_precedence: FlushPrecedence,
+    /// cards exchanged between deals in a match - the first entry is
+    /// the number swapped between the first and last finishers, the
+    /// second between the second and second to last, and so on
+    #[serde(default)]
+    pub card_exchange: [u8; 4],
+    /// when there is no previous deal or dealer to go by, the
+    /// holder of the lowest card starts
+    #[serde(default)]
+    pub starting_rule: StartingRule,
+    #[serde(default)]
+    pub hand_rules: HandRules,
+    #[serde(default)]
+    pub bombs: Option<BombRule>,
+    /// flushes that tie on their top card are compared card by card
+    /// down their ranks, poker style
+    #[serde(default)]
+    pub flush_kickers: bool,
+}
+
+/// Reversals on, flushes ranked by their top card, and the default
+/// of every other rule
+pub const DEFAULT_RULESET: Ruleset = Ruleset {
+    reversals_enabled: true,
+    flush_precedence: FlushPrecedence::Rank,
+    card_exchange: [0; 4],
+    starting_rule: StartingRule::LowestCard,
+    hand_rules: DEFAULT_HAND_RULES,
+    bombs: None,
+    flush_kickers: false,
+};
+
+impl Default for Ruleset {
+    fn default() -> Ruleset {
+        DEFAULT_RULESET
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_straight_rule_adds_runs_to_the_last() {
+        let wheel = [Rank::Ace, Rank::Two, Rank::Three, Rank::Four, Rank::Five];
+        let two_low = [Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six];
+
+        assert_eq!(StraightRule::NoWrap.get_runs().len(), 9);
+        assert!(!StraightRule::NoWrap.get_runs().contains(&wheel));
+        assert!(StraightRule::LowAce.get_runs().contains(&wheel));
+        assert!(!StraightRule::LowAce.get_runs().contains(&two_low));
+        assert!(StraightRule::LowTwo.get_runs().contains(&two_low));
+        assert_eq!(StraightRule::WrapAround.get_runs().len(), 13);
+        assert_eq!(
+            StraightRule::WrapAround.get_runs()[10],
+            [Rank::King, Rank::Ace, Rank::Two, Rank::Three, Rank::Four]
+        );
+    }
+
+    #[test]
+    fn bombs_only_beat_small_enough_hands() {
+        let bombs = BombRule {
+            four_of_a_kind: true,
+            straight_flush: false,
+            five_of_a_kind: false,
+            beats_up_to: 2,
+        };
+        let hand = |cards: &str| cards.parse::<Hand>().unwrap();
+        let four_of_a_kind = hand("9C 9H 9D 9S 3C");
+        let straight_flush = hand("3C 4C 5C 6C 7C");
+
+        assert!(bombs.can_bomb(hand("2S 2H"), four_of_a_kind));
+        assert!(!bombs.can_bomb(hand("2S 2H"), straight_flush));
+        assert!(!bombs.can_bomb(hand("4C 4H 4D"), four_of_a_kind));
+        assert!(!bombs.can_bomb(hand("pass"), four_of_a_kind));
+        assert!(!bombs.can_bomb(straight_flush, four_of_a_kind));
+    }
+}
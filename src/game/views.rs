@@ -0,0 +1,161 @@
+use super::{Game, Hand, Ruleset, SubmitError, Turn};
+use crate::cards::{Card, Rank, Suit};
+use serde::{Deserialize, Serialize};
+
+/// The public part of a player - everyone can see how many
+/// cards they hold, but not which
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSummary {
+    pub id: String,
+    pub card_count: usize,
+}
+
+/// The state of a game as seen by one of its players
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub player_id: String,
+    pub hand: Vec<Card>,
+    /// the other players, in seating order
+    pub opponents: Vec<PlayerSummary>,
+    /// where the player sits - the opponents before this index sit
+    /// before them
+    #[serde(default)]
+    pub seat: usize,
+    pub last_move: Option<Hand>,
+    pub last_player: Option<String>,
+    pub next_player: Option<String>,
+    pub winners: Vec<String>,
+    pub suit_order: [Suit; 4],
+    pub rank_order: [Rank; 13],
+    pub ruleset: Ruleset,
+    pub num_decks: u8,
+    pub num_jokers: u8,
+    pub history: Vec<Turn>,
+}
+
+/// The state of a game with every player's cards hidden
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpectatorView {
+    /// all players, in seating order
+    pub players: Vec<PlayerSummary>,
+    pub last_move: Option<Hand>,
+    pub last_player: Option<String>,
+    pub next_player: Option<String>,
+    pub winners: Vec<String>,
+    pub suit_order: [Suit; 4],
+    pub rank_order: [Rank; 13],
+    pub ruleset: Ruleset,
+    pub num_decks: u8,
+    pub num_jokers: u8,
+    pub history: Vec<Turn>,
+}
+
+impl PlayerView {
+    pub fn from_game(
+        game: &Game,
+        player_id: &str
+    ) -> Result<PlayerView, SubmitError> {
+        let player = game.get_player(player_id)
+            .ok_or(SubmitError::UnknownPlayer)?;
+        let spectator_view = SpectatorView::from_game(game);
+        let seat = spectator_view.players.iter()
+            .position(|p| p.id == player_id)
+            .unwrap_or(0);
+
+        Ok(PlayerView {
+            player_id: player_id.to_string(),
+            hand: player.get_hand(),
+            opponents: spectator_view.players.into_iter()
+                .filter(|p| p.id != player_id)
+                .collect(),
+            seat,
+            last_move: spectator_view.last_move,
+            last_player: spectator_view.last_player,
+            next_player: spectator_view.next_player,
+            winners: spectator_view.winners,
+            suit_order: spectator_view.suit_order,
+            rank_order: spectator_view.rank_order,
+            ruleset: spectator_view.ruleset,
+            num_decks: spectator_view.num_decks,
+            num_jokers: spectator_view.num_jokers,
+            history: spectator_view.history,
+        })
+    }
+}
+
+impl SpectatorView {
+    pub fn from_game(game: &Game) -> SpectatorView {
+        let round = game.get_round();
+
+        SpectatorView {
+            players: round.get_players().iter()
+                .map(|p| PlayerSummary {
+                    id: p.get_id().to_string(),
+                    card_count: p.get_card_count(),
+                })
+                .collect(),
+            last_move: round.get_last_move(),
+            last_player: round.get_last_player(),
+            next_player: round.get_next_player(),
+            winners: game.get_winners(),
+            suit_order: round.get_suit_order(),
+            rank_order: round.get_rank_order(),
+            ruleset: game.get_ruleset(),
+            num_decks: game.get_num_decks(),
+            num_jokers: game.get_num_jokers(),
+            history: game.get_history(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::DEFAULT_RULESET;
+
+    fn get_game() -> Game {
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        Game::new_with_seed(1, 0, &ids, get_suit_array(), DEFAULT_RULESET, 5)
+    }
+
+    #[test]
+    fn a_player_sees_their_own_cards_and_opponent_counts() {
+        let game = get_game();
+
+        let view = game.view_for("b").unwrap();
+
+        assert_eq!(view.hand, game.get_player("b").unwrap().get_hand());
+        assert_eq!(
+            view.opponents,
+            vec![
+                PlayerSummary { id: "a".to_string(), card_count: 18 },
+                PlayerSummary { id: "c".to_string(), card_count: 17 },
+            ]
+        );
+        assert_eq!(view.seat, 1);
+        assert_eq!(view.next_player, game.get_next_player());
+    }
+
+    #[test]
+    fn unknown_players_have_no_view() {
+        assert_eq!(
+            get_game().view_for("z"),
+            Err(SubmitError::UnknownPlayer)
+        );
+    }
+
+    #[test]
+    fn spectators_only_see_card_counts() {
+        let game = get_game();
+
+        let view = game.spectator_view();
+
+        let counts: Vec<usize> = view.players.iter()
+            .map(|p| p.card_count)
+            .collect();
+        assert_eq!(counts, vec![18, 17, 17]);
+        assert_eq!(view.suit_order, game.get_suit_order());
+        assert_eq!(view.ruleset, DEFAULT_RULESET);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod cards;
 pub mod game;
 pub mod ai;
+mod wasm;
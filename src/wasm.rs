@@ -0,0 +1,484 @@
+use crate::ai::CardTracker;
+use crate::game::{Game, Ruleset};
+use crate::cards::{PlayedCard, Suit};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Display;
+use wasm_bindgen::prelude::*;
+
+/// The shapes of the JSON values, checked against serde in the tests.
+/// Only the wasm32 build reads it outside of them
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+const TS_TYPES: &str = r#"
+export type Suit = "clubs" | "hearts" | "diamonds" | "spades";
+
+export type Rank = "three" | "four" | "five" | "six" | "seven" | "eight"
+    | "nine" | "ten" | "jack" | "queen" | "king" | "ace" | "two";
+
+export type Card =
+    | { type: "joker", deck_id: number }
+    | { type: "standard", deck_id: number, rank: Rank, suit: Suit };
+
+export interface PlayedCard {
+    rank: Rank;
+    suit: Suit;
+    is_joker: boolean;
+}
+
+export type TrickType = "straight" | "flush" | "fullhouse"
+    | "fourofakind" | "straightflush" | "fiveofakind";
+
+export type Hand =
+    | { type: "pass" }
+    | { type: "single", cards: PlayedCard }
+    | { type: "pair", cards: [PlayedCard, PlayedCard] }
+    | { type: "prial", cards: [PlayedCard, PlayedCard, PlayedCard] }
+    | { type: "twopair", cards: [PlayedCard, PlayedCard, PlayedCard, PlayedCard] }
+    | { type: "fivecardtrick", cards: {
+        trick_type: TrickType,
+        cards: [PlayedCard, PlayedCard, PlayedCard, PlayedCard, PlayedCard]
+    } };
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_SECTION: &str = TS_TYPES;
+
+/// JavaScript API - structured values are passed as JSON strings
+/// and errors are thrown with the name of the `SubmitError`
+#[wasm_bindgen]
+impl Game {
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(
+        num_decks: u8,
+        num_jokers: u8,
+        player_ids: &str,
+        suit_order: &str,
+        ruleset: &str,
+    ) -> Result<Game, ApiError> {
+        let player_ids: Vec<String> = from_json(player_ids)?;
+        let suit_order: [Suit; 4] = from_json(suit_order)?;
+        let ruleset: Ruleset = from_json(ruleset)?;
+
+        Ok(Game::new(num_decks, num_jokers, &player_ids, suit_order, ruleset))
+    }
+
+    #[wasm_bindgen(js_name = deserialize)]
+    pub fn js_deserialize(state: &str) -> Result<Game, ApiError> {
+        from_json(state)
+    }
+
+    #[wasm_bindgen(js_name = serialize)]
+    pub fn js_serialize(&self) -> Result<String, ApiError> {
+        to_json(self)
+    }
+
+    #[wasm_bindgen(js_name = playMove)]
+    pub fn js_play_move(
+        &mut self,
+        player_id: &str,
+        cards: &str
+    ) -> Result<(), ApiError> {
+        let cards: Vec<PlayedCard> = from_json(cards)?;
+
+        self.play_move(player_id, cards).map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = suggestMove)]
+    pub fn js_suggest_move(&self, player_id: &str) -> Result<String, ApiError> {
+        let cards = self.suggest_move(player_id).map_err(js_error)?;
+
+        to_json(&cards)
+    }
+
+    #[wasm_bindgen(js_name = checkMove)]
+    pub fn js_check_move(&self, cards: &str) -> Result<bool, ApiError> {
+        let cards: Vec<PlayedCard> = from_json(cards)?;
+
+        Ok(self.check_move(cards))
+    }
+
+    #[wasm_bindgen(js_name = getPlayer)]
+    pub fn js_get_player(&self, player_id: &str) -> Result<String, ApiError> {
+        to_json(&self.get_player(player_id))
+    }
+
+    #[wasm_bindgen(js_name = getNextPlayer)]
+    pub fn js_get_next_player(&self) -> Option<String> {
+        self.get_next_player()
+    }
+
+    #[wasm_bindgen(js_name = getLastMove)]
+    pub fn js_get_last_move(&self) -> Result<String, ApiError> {
+        to_json(&self.get_last_move())
+    }
+
+    #[wasm_bindgen(js_name = getWinners)]
+    pub fn js_get_winners(&self) -> Result<String, ApiError> {
+        to_json(&self.get_winners())
+    }
+
+    #[wasm_bindgen(js_name = viewFor)]
+    pub fn js_view_for(&self, player_id: &str) -> Result<String, ApiError> {
+        let view = self.view_for(player_id).map_err(js_error)?;
+
+        to_json(&view)
+    }
+
+    /// The cards the player hasn't seen yet, as `Card[]`
+    #[wasm_bindgen(js_name = unseenCards)]
+    pub fn js_unseen_cards(&self, player_id: &str) -> Result<String, ApiError> {
+        let view = self.view_for(player_id).map_err(js_error)?;
+
+        to_json(&CardTracker::from_view(&view).get_unseen_cards())
+    }
+
+    #[wasm_bindgen(js_name = spectatorView)]
+    pub fn js_spectator_view(&self) -> Result<String, ApiError> {
+        to_json(&self.spectator_view())
+    }
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, ApiError> {
+    serde_json::from_str(json).map_err(js_error)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, ApiError> {
+    serde_json::to_string(value).map_err(js_error)
+}
+
+fn js_error<E: Display>(error: E) -> ApiError {
+    ApiError(error.to_string())
+}
+
+/// An error thrown to JavaScript as an `Error`, kept as its message
+/// until it crosses into JavaScript
+#[derive(Debug, PartialEq)]
+pub struct ApiError(String);
+
+impl From<ApiError> for JsValue {
+    fn from(error: ApiError) -> JsValue {
+        JsError::new(&error.0).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::*;
+    use crate::game::{Hand, SubmitError, Trick, TrickType};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    /// The subset of TypeScript used by `TS_TYPES`
+    #[derive(Debug)]
+    enum TsType {
+        Literal(String),
+        Number,
+        Boolean,
+        Named(String),
+        Union(Vec<TsType>),
+        Tuple(Vec<TsType>),
+        Object(Vec<(String, TsType)>),
+    }
+
+    struct TsParser {
+        tokens: Vec<String>,
+        position: usize,
+    }
+
+    impl TsParser {
+        fn new(source: &str) -> TsParser {
+            let mut tokens = vec![];
+            let mut chars = source.chars().peekable();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    chars.next();
+                } else if c == '"' {
+                    chars.next();
+                    let literal: String = chars.by_ref()
+                        .take_while(|&c| c != '"')
+                        .collect();
+                    tokens.push(format!("\"{}\"", literal));
+                } else if c.is_alphanumeric() || c == '_' {
+                    let mut word = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if !c.is_alphanumeric() && c != '_' {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+                    tokens.push(word);
+                } else {
+                    tokens.push(c.to_string());
+                    chars.next();
+                }
+            }
+
+            TsParser { tokens, position: 0 }
+        }
+
+        fn next(&mut self) -> String {
+            self.position += 1;
+            self.tokens[self.position - 1].clone()
+        }
+
+        fn skip(&mut self, token: &str) -> bool {
+            let found = self.tokens.get(self.position)
+                .is_some_and(|t| t == token);
+            if found {
+                self.position += 1;
+            }
+            found
+        }
+
+        fn expect(&mut self, token: &str) {
+            assert_eq!(self.next(), token);
+        }
+
+        fn declarations(&mut self) -> HashMap<String, TsType> {
+            let mut declarations = HashMap::new();
+            while self.position < self.tokens.len() {
+                self.expect("export");
+                let keyword = self.next();
+                let name = self.next();
+                let ts_type = if keyword == "interface" {
+                    self.expect("{");
+                    self.object()
+                } else {
+                    self.expect("=");
+                    let ts_type = self.union();
+                    self.expect(";");
+                    ts_type
+                };
+                declarations.insert(name, ts_type);
+            }
+            declarations
+        }
+
+        fn union(&mut self) -> TsType {
+            self.skip("|");
+            let mut options = vec![self.primary()];
+            while self.skip("|") {
+                options.push(self.primary());
+            }
+            if options.len() == 1 {
+                options.remove(0)
+            } else {
+                TsType::Union(options)
+            }
+        }
+
+        fn primary(&mut self) -> TsType {
+            let token = self.next();
+            match token.as_str() {
+                "{" => self.object(),
+                "[" => {
+                    let mut items = vec![];
+                    while !self.skip("]") {
+                        items.push(self.union());
+                        self.skip(",");
+                    }
+                    TsType::Tuple(items)
+                },
+                "number" => TsType::Number,
+                "boolean" => TsType::Boolean,
+                _ if token.starts_with('"') => {
+                    TsType::Literal(token.trim_matches('"').to_string())
+                },
+                _ => TsType::Named(token),
+            }
+        }
+
+        fn object(&mut self) -> TsType {
+            let mut fields = vec![];
+            while !self.skip("}") {
+                let key = self.next();
+                self.expect(":");
+                fields.push((key, self.union()));
+                if !self.skip(",") {
+                    self.skip(";");
+                }
+            }
+            TsType::Object(fields)
+        }
+    }
+
+    fn matches(
+        ts_type: &TsType,
+        value: &Value,
+        declarations: &HashMap<String, TsType>
+    ) -> bool {
+        match ts_type {
+            TsType::Literal(literal) => value.as_str() == Some(literal),
+            TsType::Number => value.is_number(),
+            TsType::Boolean => value.is_boolean(),
+            TsType::Named(name) => {
+                matches(&declarations[name], value, declarations)
+            },
+            TsType::Union(options) => options.iter()
+                .any(|option| matches(option, value, declarations)),
+            TsType::Tuple(items) => value.as_array().is_some_and(|values| {
+                values.len() == items.len() &&
+                    items.iter().zip(values).all(|(item, value)| {
+                        matches(item, value, declarations)
+                    })
+            }),
+            TsType::Object(fields) => value.as_object().is_some_and(|map| {
+                map.len() == fields.len() &&
+                    fields.iter().all(|(key, field)| {
+                        map.get(key).is_some_and(|value| {
+                            matches(field, value, declarations)
+                        })
+                    })
+            }),
+        }
+    }
+
+    fn assert_declared_as<T: Serialize>(name: &str, values: &[T]) {
+        let declarations = TsParser::new(TS_TYPES).declarations();
+
+        for value in values {
+            let json = serde_json::to_value(value).unwrap();
+            assert!(
+                matches(&TsType::Named(name.to_string()), &json, &declarations),
+                "{} doesn't match the declared {}",
+                json,
+                name
+            );
+        }
+    }
+
+    fn get_game() -> Game {
+        Game::js_new(
+            1,
+            0,
+            r#"["a", "b"]"#,
+            r#"["clubs", "hearts", "diamonds", "spades"]"#,
+            r#"{"reversals_enabled": true, "flush_precedence": "Rank"}"#
+        ).unwrap()
+    }
+
+    #[test]
+    fn a_game_can_be_created_from_json() {
+        let game = get_game();
+
+        assert_eq!(game.get_player_ids(), vec!["a", "b"]);
+        assert_eq!(game.get_suit_order(), get_suit_array());
+    }
+
+    #[test]
+    fn state_survives_serializing() {
+        let game = get_game();
+
+        let state = game.js_serialize().unwrap();
+        let restored = Game::js_deserialize(&state).unwrap();
+
+        assert_eq!(restored.js_serialize().unwrap(), state);
+    }
+
+    #[test]
+    fn moves_are_passed_as_json() {
+        let mut game = get_game();
+        let player = game.js_get_next_player().unwrap();
+        let suggestion = game.js_suggest_move(&player).unwrap();
+
+        assert!(game.js_check_move(&suggestion).unwrap());
+        game.js_play_move(&player, &suggestion).unwrap();
+
+        let last_move: Option<crate::game::Hand> = serde_json::from_str(
+            &game.js_get_last_move().unwrap()
+        ).unwrap();
+        assert!(last_move.is_some());
+    }
+
+    #[test]
+    fn unseen_cards_leave_out_the_players_own_hand() {
+        let game = get_game();
+        let unseen: Vec<Card> = serde_json::from_str(
+            &game.js_unseen_cards("a").unwrap()
+        ).unwrap();
+        let hand = game.get_player("a").unwrap().get_hand();
+
+        assert_eq!(unseen.len(), 52 - hand.len());
+        assert!(unseen.iter().all(|c| !hand.contains(c)));
+    }
+
+    #[test]
+    fn serialized_values_match_the_typescript_declarations() {
+        let card = PlayedCard::new(Rank::Three, Suit::Clubs, false);
+        let joker = PlayedCard::new(Rank::Two, Suit::Spades, true);
+        let trick_types = [
+            TrickType::Straight,
+            TrickType::Flush,
+            TrickType::FullHouse,
+            TrickType::FourOfAKind,
+            TrickType::StraightFlush,
+            TrickType::FiveOfAKind,
+        ];
+        let mut hands = vec![
+            Hand::Pass,
+            Hand::Single(card),
+            Hand::Pair(card, joker),
+            Hand::Prial(card, card, joker),
+            Hand::TwoPair(card, card, joker, joker),
+        ];
+        hands.extend(trick_types.iter().map(|&trick_type| {
+            Hand::FiveCardTrick(Trick { trick_type, cards: [card; 5] })
+        }));
+
+        assert_declared_as("Suit", &get_suit_array());
+        assert_declared_as("Rank", &get_rank_array());
+        assert_declared_as("TrickType", &trick_types);
+        assert_declared_as("PlayedCard", &[card, joker]);
+        assert_declared_as("Card", &[
+            Card::Joker { deck_id: 0 },
+            Card::Standard { deck_id: 1, rank: Rank::Ace, suit: Suit::Hearts },
+        ]);
+        assert_declared_as("Hand", &hands);
+    }
+
+    #[test]
+    fn the_typescript_matcher_rejects_other_shapes() {
+        let declarations = TsParser::new(TS_TYPES).declarations();
+        let hand = TsType::Named("Hand".to_string());
+
+        for json in &[
+            r#"{"type": "single"}"#,
+            r#"{"type": "pair", "cards": [
+                {"rank": "three", "suit": "clubs", "is_joker": false}
+            ]}"#,
+            r#"{"type": "pass", "cards": null}"#,
+        ] {
+            let value: Value = serde_json::from_str(json).unwrap();
+            assert!(!matches(&hand, &value, &declarations), "{}", json);
+        }
+    }
+
+    #[test]
+    fn invalid_moves_throw_the_submit_error_name() {
+        let mut game = get_game();
+        let player = game.js_get_next_player().unwrap();
+        let other = if player == "a" { "b" } else { "a" };
+        let suggestion = game.js_suggest_move(&player).unwrap();
+
+        assert_eq!(
+            game.js_play_move(other, &suggestion),
+            Err(ApiError("NotCurrentPlayer".to_string()))
+        );
+        assert_eq!(
+            game.js_play_move(&player, "[]"),
+            Err(ApiError("FirstRoundPass".to_string()))
+        );
+        assert!(game.js_play_move(&player, "not json").is_err());
+    }
+
+    #[test]
+    fn errors_are_named_after_the_submit_error() {
+        assert_eq!(SubmitError::GameOver.to_string(), "GameOver");
+        assert_eq!(
+            SubmitError::PlayerDoesntHaveCard(vec![]).to_string(),
+            "PlayerDoesntHaveCard"
+        );
+    }
+}